@@ -27,13 +27,11 @@ impl Completer for FixedCompleter {
         if let Some(keyword) = input.last_alphabetic_keyword() {
             for reserved_keyword in GITQL_RESERVED_KEYWORDS {
                 if reserved_keyword.starts_with(&keyword) {
-                    let suggestion = Suggestion {
-                        content: StyledBuffer::from(reserved_keyword),
-                        span: Span {
-                            start: input.len() - keyword.len(),
-                            end: input.len(),
-                        },
-                    };
+                    let suggestion = Suggestion::new(
+                        StyledBuffer::from(reserved_keyword),
+                        Span::new(input.len() - keyword.chars().count(), input.len()),
+                    )
+                    .with_description("keyword");
                     suggestions.push(suggestion);
                 }
             }
@@ -58,6 +56,14 @@ fn main() {
         },
         LineEditorEvent::ToggleAutoComplete,
     );
+    bindings.register_binding(
+        KeyCombination {
+            key_kind: lineeditor::KeyEventKind::Press,
+            modifier: KeyModifiers::SHIFT,
+            key_code: lineeditor::KeyCode::BackTab,
+        },
+        LineEditorEvent::AutoCompletePrevious,
+    );
     bindings.register_common_control_bindings();
     bindings.register_common_navigation_bindings();
     bindings.register_common_edit_bindings();
@@ -68,6 +68,11 @@ impl Highlighter for GitQLHighlighter {
             i += 1;
         }
     }
+
+    // The styles depend only on the text, they are reused when only the cursor moves
+    fn is_cacheable(&self) -> bool {
+        true
+    }
 }
 
 fn main() {
@@ -0,0 +1,42 @@
+use lineeditor::styled_buffer::StyledBuffer;
+use lineeditor::LineEditor;
+use lineeditor::LineEditorResult;
+use lineeditor::StringPrompt;
+use lineeditor::ValidationResult;
+use lineeditor::Validator;
+
+#[derive(Default)]
+pub struct BracketsValidator {}
+
+impl Validator for BracketsValidator {
+    fn validate(&self, buffer: &StyledBuffer) -> ValidationResult {
+        let mut depth = 0;
+        for ch in buffer.literal().chars() {
+            match ch {
+                '(' | '{' | '[' => depth += 1,
+                ')' | '}' | ']' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        if depth > 0 {
+            ValidationResult::Incomplete
+        } else {
+            ValidationResult::Complete
+        }
+    }
+}
+
+fn main() {
+    let prompt = StringPrompt::new("prompt> ".to_string());
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+    line_editor.set_validator(Box::<BracketsValidator>::default());
+
+    let bindings = line_editor.keybinding();
+    bindings.register_common_control_bindings();
+
+    if let Ok(LineEditorResult::Success(line)) = line_editor.read_line() {
+        println!();
+        println!("Line {}", line);
+    }
+}
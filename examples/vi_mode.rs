@@ -0,0 +1,36 @@
+use lineeditor::edit_mode::EditMode;
+use lineeditor::edit_mode::ViMode;
+use lineeditor::styled_buffer::StyledBuffer;
+use lineeditor::LineEditor;
+use lineeditor::LineEditorResult;
+use lineeditor::Prompt;
+
+pub struct ViModePrompt {}
+
+impl Prompt for ViModePrompt {
+    fn prompt(&self) -> StyledBuffer {
+        StyledBuffer::from("> ")
+    }
+
+    fn prompt_with_mode(&self, mode: ViMode) -> StyledBuffer {
+        match mode {
+            ViMode::Normal => StyledBuffer::from("[N] > "),
+            ViMode::Insert => StyledBuffer::from("[I] > "),
+        }
+    }
+}
+
+fn main() {
+    let prompt = ViModePrompt {};
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+    line_editor.set_edit_mode(EditMode::Vi);
+
+    let bindings = line_editor.keybinding();
+    bindings.register_common_control_bindings();
+    bindings.register_common_navigation_bindings();
+    bindings.register_common_edit_bindings();
+
+    if let Ok(LineEditorResult::Success(line)) = line_editor.read_line() {
+        println!("Line {}", line);
+    }
+}
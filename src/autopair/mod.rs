@@ -17,11 +17,34 @@ pub const DEFAULT_PAIRS: &[(char, char)] = &[
 pub trait AutoPair {
     /// The action that will handle the current styled buffer as a line
     fn complete_pair(&self, buffer: &mut StyledBuffer);
+
+    /// Replace the pairs used by this auto pair, called with the pairs configured
+    /// by [`crate::LineEditor::set_pairs`]
+    fn set_pairs(&mut self, _pairs: &[(char, char)]) {}
+
+    /// Returns true if typing the character should move the cursor over the same character
+    /// on the right instead of inserting it, like typing `)` before the `)` of a completed pair
+    fn skip_over(&self, _buffer: &StyledBuffer, _ch: char) -> bool {
+        false
+    }
+
+    /// Returns true if the cursor is between the open and close characters of an empty pair,
+    /// so Backspace deletes both of them
+    fn is_inside_empty_pair(&self, _buffer: &StyledBuffer) -> bool {
+        false
+    }
+
+    /// Called when Enter is pressed, returns true if the pair around the cursor is expanded
+    /// to multiple lines, in that case the buffer is not validated or submitted
+    fn expand_new_line(&self, _buffer: &mut StyledBuffer) -> bool {
+        false
+    }
 }
 
 /// Auto pair complete that depend on a map of pairs
 pub struct DefaultAutoPair {
     pairs: HashMap<char, char>,
+    expand_new_lines: bool,
 }
 
 /// Create instance of DefaultAutoPair with default pairs [`DEFAULT_PAIRS`]
@@ -31,14 +54,27 @@ impl Default for DefaultAutoPair {
         for pair in DEFAULT_PAIRS {
             pairs.insert(pair.0, pair.1);
         }
-        Self { pairs }
+        Self {
+            pairs,
+            expand_new_lines: false,
+        }
     }
 }
 
 impl DefaultAutoPair {
     /// Create instance of DefaultAutoPair with custom pairs
     pub fn with_pairs(pairs: HashMap<char, char>) -> Self {
-        Self { pairs }
+        Self {
+            pairs,
+            expand_new_lines: false,
+        }
+    }
+
+    /// Enable or Disable expanding a brace like pair to three lines when Enter is pressed
+    /// between its characters, the cursor is left indented on the middle line
+    pub fn expand_new_lines(mut self, enable: bool) -> Self {
+        self.expand_new_lines = enable;
+        self
     }
 }
 
@@ -54,4 +90,151 @@ impl AutoPair for DefaultAutoPair {
             }
         }
     }
+
+    /// Replace the pairs map
+    fn set_pairs(&mut self, pairs: &[(char, char)]) {
+        self.pairs = pairs.iter().copied().collect();
+    }
+
+    /// Skip over the closing character of a pair if it is already on the right of the cursor
+    fn skip_over(&self, buffer: &StyledBuffer, ch: char) -> bool {
+        self.pairs.values().any(|close| *close == ch)
+            && buffer.char_at(buffer.position()) == Some(ch)
+    }
+
+    /// Check if the characters around the cursor are an open character and its pair
+    fn is_inside_empty_pair(&self, buffer: &StyledBuffer) -> bool {
+        let position = buffer.position();
+        if position == 0 {
+            return false;
+        }
+
+        match (buffer.char_at(position - 1), buffer.char_at(position)) {
+            (Some(open), Some(close)) => self.pairs.get(&open) == Some(&close),
+            _ => false,
+        }
+    }
+
+    /// Expand an empty pair with different open and close characters, like `{}`, keeping
+    /// the indentation of the current line and indenting the middle line by four spaces
+    fn expand_new_line(&self, buffer: &mut StyledBuffer) -> bool {
+        let position = buffer.position();
+        if !self.expand_new_lines || position == 0 || position >= buffer.len() {
+            return false;
+        }
+
+        match (buffer.char_at(position - 1), buffer.char_at(position)) {
+            (Some(open), Some(close)) if open != close => {
+                if self.pairs.get(&open) != Some(&close) {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+
+        let line_start = (0..position)
+            .rev()
+            .find(|i| buffer.char_at(*i) == Some('\n'))
+            .map_or(0, |i| i + 1);
+        let indentation: String = (line_start..position)
+            .map_while(|i| buffer.char_at(i).filter(|ch| *ch == ' ' || *ch == '\t'))
+            .collect();
+
+        buffer.insert_string(&format!("\n{}    ", indentation));
+        let middle_line_end = buffer.position();
+        buffer.insert_string(&format!("\n{}", indentation));
+        buffer.set_position(middle_line_end);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer_at(text: &str, position: usize) -> StyledBuffer {
+        let mut buffer = StyledBuffer::from(text);
+        buffer.set_position(position);
+        buffer
+    }
+
+    #[test]
+    fn inside_empty_pair() {
+        let auto_pair = DefaultAutoPair::default();
+        assert!(auto_pair.is_inside_empty_pair(&buffer_at("()", 1)));
+        assert!(auto_pair.is_inside_empty_pair(&buffer_at("a[]b", 2)));
+        assert!(auto_pair.is_inside_empty_pair(&buffer_at("\"\"", 1)));
+    }
+
+    #[test]
+    fn not_inside_empty_pair() {
+        let auto_pair = DefaultAutoPair::default();
+        assert!(!auto_pair.is_inside_empty_pair(&buffer_at("(]", 1)));
+        assert!(!auto_pair.is_inside_empty_pair(&buffer_at(")(", 1)));
+        assert!(!auto_pair.is_inside_empty_pair(&buffer_at("ab", 1)));
+        assert!(!auto_pair.is_inside_empty_pair(&buffer_at("()", 0)));
+    }
+
+    #[test]
+    fn inside_empty_pair_at_the_end() {
+        let auto_pair = DefaultAutoPair::default();
+        assert!(!auto_pair.is_inside_empty_pair(&buffer_at("(", 1)));
+        assert!(!auto_pair.is_inside_empty_pair(&buffer_at("", 0)));
+    }
+
+    #[test]
+    fn skip_over_closing_characters() {
+        let auto_pair = DefaultAutoPair::default();
+        assert!(auto_pair.skip_over(&buffer_at("()", 1), ')'));
+        assert!(auto_pair.skip_over(&buffer_at("[]", 1), ']'));
+        assert!(auto_pair.skip_over(&buffer_at("{}", 1), '}'));
+        assert!(auto_pair.skip_over(&buffer_at("\"\"", 1), '"'));
+        assert!(auto_pair.skip_over(&buffer_at("''", 1), '\''));
+    }
+
+    #[test]
+    fn skip_over_only_the_same_closing_character() {
+        let auto_pair = DefaultAutoPair::default();
+        assert!(!auto_pair.skip_over(&buffer_at("()", 1), ']'));
+        assert!(!auto_pair.skip_over(&buffer_at("(a", 1), ')'));
+        assert!(!auto_pair.skip_over(&buffer_at("((", 1), '('));
+    }
+
+    #[test]
+    fn skip_over_at_the_end() {
+        let auto_pair = DefaultAutoPair::default();
+        assert!(!auto_pair.skip_over(&buffer_at("(", 1), ')'));
+        assert!(!auto_pair.skip_over(&buffer_at("\"", 1), '"'));
+        assert!(!auto_pair.skip_over(&buffer_at("", 0), '}'));
+    }
+
+    #[test]
+    fn expand_new_line_inside_pair() {
+        let auto_pair = DefaultAutoPair::default().expand_new_lines(true);
+        let mut buffer = buffer_at("  {}", 3);
+        assert!(auto_pair.expand_new_line(&mut buffer));
+        assert_eq!(buffer.literal(), "  {\n      \n  }");
+        assert_eq!(buffer.position(), 10);
+    }
+
+    #[test]
+    fn expand_new_line_at_the_end() {
+        let auto_pair = DefaultAutoPair::default().expand_new_lines(true);
+        let mut buffer = buffer_at("{", 1);
+        assert!(!auto_pair.expand_new_line(&mut buffer));
+        assert_eq!(buffer.literal(), "{");
+
+        let mut buffer = buffer_at("", 0);
+        assert!(!auto_pair.expand_new_line(&mut buffer));
+    }
+
+    #[test]
+    fn expand_new_line_disabled_or_same_characters() {
+        let mut buffer = buffer_at("{}", 1);
+        assert!(!DefaultAutoPair::default().expand_new_line(&mut buffer));
+
+        let auto_pair = DefaultAutoPair::default().expand_new_lines(true);
+        let mut buffer = buffer_at("\"\"", 1);
+        assert!(!auto_pair.expand_new_line(&mut buffer));
+    }
 }
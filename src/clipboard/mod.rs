@@ -0,0 +1,135 @@
+use std::io::Write;
+
+use ::clipboard::ClipboardContext;
+use ::clipboard::ClipboardProvider;
+
+use crate::Output;
+
+/// The Clipboard trait, Implementers of this trait store the text that is cut or copied
+/// from the visual selection and provide it back on paste
+pub trait Clipboard {
+    /// Get the current clipboard text, or None if it is empty or unavailable
+    fn get(&mut self) -> Option<String>;
+
+    /// Store the text in the clipboard
+    ///
+    /// Returns false if the text could not be stored
+    fn set(&mut self, text: &str) -> bool;
+}
+
+/// Clipboard backed by the platform clipboard, does nothing if it is unavailable
+#[derive(Default)]
+pub struct SystemClipboard;
+
+impl Clipboard for SystemClipboard {
+    fn get(&mut self) -> Option<String> {
+        let mut context = ClipboardContext::new().ok()?;
+        context.get_contents().ok()
+    }
+
+    fn set(&mut self, text: &str) -> bool {
+        let Ok(mut context) = ClipboardContext::new() else {
+            return false;
+        };
+        context.set_contents(text.to_string()).is_ok()
+    }
+}
+
+/// In memory Clipboard that lives as long as the line editor
+#[derive(Default)]
+pub struct MemoryClipboard {
+    contents: Option<String>,
+}
+
+impl Clipboard for MemoryClipboard {
+    fn get(&mut self) -> Option<String> {
+        self.contents.clone()
+    }
+
+    fn set(&mut self, text: &str) -> bool {
+        self.contents = Some(text.to_string());
+        true
+    }
+}
+
+/// Clipboard that sends the text to the terminal using the OSC 52 escape sequence,
+/// so copy works over SSH where there is no system clipboard
+///
+/// Reading the terminal clipboard is not supported, so paste has no effect
+#[derive(Default)]
+pub struct Osc52Clipboard {
+    output: Output,
+}
+
+impl Osc52Clipboard {
+    /// Create instance of Osc52Clipboard that writes to the output,
+    /// the default writes to the standard error like the line editor
+    pub fn new(output: Output) -> Self {
+        Osc52Clipboard { output }
+    }
+}
+
+impl Clipboard for Osc52Clipboard {
+    fn get(&mut self) -> Option<String> {
+        None
+    }
+
+    fn set(&mut self, text: &str) -> bool {
+        let sequence = format!("\x1b]52;c;{}\x07", encode_base64(text.as_bytes()));
+        self.output.write_all(sequence.as_bytes()).is_ok() && self.output.flush().is_ok()
+    }
+}
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+        let group = (b0 << 16) | (b1 << 8) | b2;
+
+        encoded.push(BASE64_CHARS[(group >> 18) as usize & 63] as char);
+        encoded.push(BASE64_CHARS[(group >> 12) as usize & 63] as char);
+        if chunk.len() > 1 {
+            encoded.push(BASE64_CHARS[(group >> 6) as usize & 63] as char);
+        } else {
+            encoded.push('=');
+        }
+        if chunk.len() > 2 {
+            encoded.push(BASE64_CHARS[group as usize & 63] as char);
+        } else {
+            encoded.push('=');
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_base64_test_vectors() {
+        // RFC 4648 section 10
+        for (text, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(encode_base64(text.as_bytes()), encoded);
+        }
+    }
+
+    #[test]
+    fn encode_base64_of_non_ascii_bytes() {
+        assert_eq!(encode_base64("é".as_bytes()), "w6k=");
+        assert_eq!(encode_base64("日本".as_bytes()), "5pel5pys");
+        assert_eq!(encode_base64(&[0x00, 0xff, 0xfe]), "AP/+");
+    }
+}
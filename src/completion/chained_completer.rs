@@ -0,0 +1,43 @@
+use crate::styled_buffer::StyledBuffer;
+
+use super::Completer;
+use super::Suggestion;
+
+/// Completer that merges the suggestions of many completers in the order they are added,
+/// for example keywords before files
+///
+/// Every suggestion keeps the span of its completer, a suggestion with the same literal
+/// as an earlier one is dropped
+#[derive(Default)]
+pub struct ChainedCompleter {
+    completers: Vec<Box<dyn Completer>>,
+}
+
+impl ChainedCompleter {
+    /// Create instance of ChainedCompleter with completers ordered from the first to query
+    pub fn with_completers(completers: Vec<Box<dyn Completer>>) -> Self {
+        ChainedCompleter { completers }
+    }
+
+    /// Add a completer that is queried after the current ones
+    pub fn add_completer(&mut self, completer: Box<dyn Completer>) {
+        self.completers.push(completer);
+    }
+}
+
+impl Completer for ChainedCompleter {
+    fn complete(&self, input: &StyledBuffer) -> Vec<Suggestion> {
+        let mut literals: Vec<String> = vec![];
+        let mut suggestions: Vec<Suggestion> = vec![];
+        for completer in &self.completers {
+            for suggestion in completer.complete(input) {
+                let literal = suggestion.content.literal();
+                if !literals.contains(&literal) {
+                    literals.push(literal);
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        suggestions
+    }
+}
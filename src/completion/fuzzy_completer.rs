@@ -0,0 +1,101 @@
+use crate::styled_buffer::StyledBuffer;
+use crate::word_splitter::WordSplitter;
+
+use super::Completer;
+use super::Span;
+use super::Suggestion;
+
+/// Completer that suggests the candidates matching the word before the cursor as a fuzzy
+/// subsequence, ordered from the best to the worst match
+///
+/// Every suggestion records the matched positions so the view can emphasize them
+pub struct FuzzyCompleter {
+    candidates: Vec<String>,
+    word_splitter: WordSplitter,
+}
+
+impl FuzzyCompleter {
+    /// Create instance of FuzzyCompleter with the list of candidates
+    pub fn new(candidates: Vec<String>) -> Self {
+        FuzzyCompleter {
+            candidates,
+            word_splitter: WordSplitter::default(),
+        }
+    }
+
+    /// Set how the buffer is split to find the word before the cursor,
+    /// [`WordSplitter::Whitespace`] by default
+    pub fn with_word_splitter(mut self, word_splitter: WordSplitter) -> Self {
+        self.word_splitter = word_splitter;
+        self
+    }
+}
+
+impl Completer for FuzzyCompleter {
+    fn complete(&self, input: &StyledBuffer) -> Vec<Suggestion> {
+        let position = input.position();
+        let start = input.current_word_span(&self.word_splitter).start;
+        let pattern = input.sub_string(start, position).unwrap_or_default();
+
+        let mut matches: Vec<(i64, &String, Vec<usize>)> = self
+            .candidates
+            .iter()
+            .filter_map(|candidate| {
+                fuzzy_match(&pattern, candidate).map(|(score, indices)| (score, candidate, indices))
+            })
+            .collect();
+
+        // Stable sort keeps the candidates order for equal matches
+        matches.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| a.1.chars().count().cmp(&b.1.chars().count()))
+        });
+
+        matches
+            .into_iter()
+            .map(|(_, candidate, indices)| {
+                let mut suggestion = Suggestion::new(
+                    StyledBuffer::from(candidate.as_str()),
+                    Span::new(start, position),
+                );
+                suggestion.match_indices = indices;
+                suggestion
+            })
+            .collect()
+    }
+}
+
+/// Match the pattern characters in order inside the candidate ignoring case, returns the score
+/// and the matched character positions in the candidate, or None if it does not match
+///
+/// Consecutive matches and matches at the start of a word score higher, an empty pattern
+/// matches every candidate with zero score
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut indices = Vec::with_capacity(pattern.chars().count());
+    let mut score = 0;
+    let mut position = 0;
+
+    for pattern_char in pattern.chars() {
+        let index = (position..candidate.len())
+            .find(|i| chars_eq_ignore_case(candidate[*i], pattern_char))?;
+
+        score += 1;
+        if index == 0 || !candidate[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if indices.last().is_some_and(|last| last + 1 == index) {
+            score += 5;
+        }
+        score -= (index - position) as i64;
+
+        indices.push(index);
+        position = index + 1;
+    }
+
+    Some((score, indices))
+}
+
+fn chars_eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::styled_buffer::StyledBuffer;
+use crate::History;
+
+use super::Completer;
+use super::Span;
+use super::Suggestion;
+
+/// Completer that suggests the History entries starting with the text before the cursor,
+/// from the newest to the oldest without duplicates, accepting one replaces the whole line
+///
+/// The History is shared with the line editor so new entries are suggested immediately
+///
+/// ```ignore
+/// let history = Rc::new(RefCell::new(VecHistory::default()));
+/// line_editor.set_history(Box::new(history.clone()));
+/// line_editor.set_completer(Box::new(HistoryCompleter::new(history)));
+/// ```
+pub struct HistoryCompleter {
+    history: Rc<RefCell<dyn History>>,
+}
+
+impl HistoryCompleter {
+    /// Create instance of HistoryCompleter with a shared History
+    pub fn new(history: Rc<RefCell<dyn History>>) -> Self {
+        HistoryCompleter { history }
+    }
+}
+
+impl Completer for HistoryCompleter {
+    fn complete(&self, input: &StyledBuffer) -> Vec<Suggestion> {
+        let prefix = input.sub_string(0, input.position()).unwrap_or_default();
+        let history = self.history.borrow();
+
+        let mut entries: Vec<String> = vec![];
+        for index in (0..history.len()).rev() {
+            if let Some(entry) = history.get(index) {
+                if entry.starts_with(&prefix) && !entries.contains(&entry) {
+                    entries.push(entry);
+                }
+            }
+        }
+
+        let prefix_len = prefix.chars().count();
+        entries
+            .into_iter()
+            .map(|entry| {
+                let mut suggestion = Suggestion::new(
+                    StyledBuffer::from(entry.as_str()),
+                    Span::new(0, input.len()),
+                );
+                suggestion.match_indices = (0..prefix_len).collect();
+                suggestion
+            })
+            .collect()
+    }
+}
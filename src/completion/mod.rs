@@ -1,6 +1,13 @@
+pub mod chained_completer;
+pub mod fuzzy_completer;
+pub mod history_completer;
+
 use crate::styled_buffer::StyledBuffer;
 
 /// A span of source code, with positions
+///
+/// Positions are character indices in the [`StyledBuffer`], the same unit used by the cursor
+/// position and [`crate::event::EditCommand::DeleteSpan`], not byte offsets of the literal string
 pub struct Span {
     pub start: usize,
     pub end: usize,
@@ -18,6 +25,28 @@ pub struct Suggestion {
     pub content: StyledBuffer,
     /// Replacement span
     pub span: Span,
+    /// Positions of the content characters that matched the input, emphasized by the view
+    pub match_indices: Vec<usize>,
+    /// Short description rendered next to the content, only the content is inserted on accept
+    pub description: Option<String>,
+}
+
+impl Suggestion {
+    /// Create a new Suggestion without matched positions
+    pub fn new(content: StyledBuffer, span: Span) -> Self {
+        Suggestion {
+            content,
+            span,
+            match_indices: vec![],
+            description: None,
+        }
+    }
+
+    /// Set the description that is displayed next to the suggestion content
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
 }
 
 /// The Completer trait, Implementers of this trait will return a list of suggestions as styled buffers
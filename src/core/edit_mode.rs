@@ -0,0 +1,300 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyModifiers;
+
+use super::event::EditCommand;
+use super::event::LineEditorEvent;
+use super::event::MovementCommand;
+use super::keybindings::KeyCombination;
+
+/// Defines how the pressed keys are turned into line editor events
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EditMode {
+    /// Every key is looked up in the Keybindings, the default
+    #[default]
+    Emacs,
+    /// Modal editing, in Normal mode the keys are vi motions and operators and
+    /// in Insert mode they are looked up in the Keybindings like the Emacs mode
+    Vi,
+}
+
+/// The current mode of the vi editing
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ViMode {
+    /// The keys are vi motions and operators
+    Normal,
+    /// The keys insert text, `Esc` switches to the Normal mode
+    #[default]
+    Insert,
+}
+
+/// Translate the keys of the vi mode into line editor events
+///
+/// The Normal mode supports the `h` `l` `j` `k` `w` `b` `e` `0` `$` motions,
+/// the `x` `p` `u` `D` `C` commands, the `d` and `c` operators with a motion or doubled,
+/// and `i` `a` `I` `A` to switch to the Insert mode
+#[derive(Default)]
+pub(crate) struct Vi {
+    mode: ViMode,
+    pending_operator: Option<char>,
+}
+
+impl Vi {
+    /// Get the current vi mode
+    pub(crate) fn mode(&self) -> ViMode {
+        self.mode
+    }
+
+    /// Set the current vi mode and cancel the pending operator
+    pub(crate) fn set_mode(&mut self, mode: ViMode) {
+        self.mode = mode;
+        self.pending_operator = None;
+    }
+
+    /// Translate the key into events, or None if the key should be looked up in the Keybindings
+    pub(crate) fn handle_key(&mut self, key: &KeyCombination) -> Option<Vec<LineEditorEvent>> {
+        if self.mode == ViMode::Insert {
+            return match key.key_code {
+                KeyCode::Esc => Some(vec![LineEditorEvent::SetViMode(ViMode::Normal)]),
+                _ => None,
+            };
+        }
+
+        let is_plain = key.modifier == KeyModifiers::NONE || key.modifier == KeyModifiers::SHIFT;
+        let KeyCode::Char(ch) = key.key_code else {
+            self.pending_operator = None;
+            return None;
+        };
+
+        if !is_plain {
+            self.pending_operator = None;
+            return None;
+        }
+
+        // Plain characters are never inserted in the Normal mode
+        let events = match self.pending_operator.take() {
+            Some(operator) => operator_events(operator, ch),
+            None => self.command_events(ch),
+        };
+        Some(events)
+    }
+
+    /// Events of a Normal mode key that is not preceded by an operator
+    fn command_events(&mut self, ch: char) -> Vec<LineEditorEvent> {
+        let movement = |command| LineEditorEvent::Movement(vec![command]);
+        let edit = |command| LineEditorEvent::Edit(vec![command]);
+        let insert_mode = LineEditorEvent::SetViMode(ViMode::Insert);
+        match ch {
+            'h' => vec![movement(MovementCommand::MoveLeftChar)],
+            'l' => vec![movement(MovementCommand::MoveRightChar)],
+            'j' => vec![LineEditorEvent::Down],
+            'k' => vec![LineEditorEvent::Up],
+            'w' => vec![movement(MovementCommand::MoveRightWord)],
+            'b' => vec![movement(MovementCommand::MoveLeftWord)],
+            'e' => vec![movement(MovementCommand::MoveRightWordEnd)],
+            '0' => vec![movement(MovementCommand::MoveToLineStart)],
+            '$' => vec![movement(MovementCommand::MoveToLineEnd)],
+            'x' => vec![edit(EditCommand::DeleteRightChar)],
+            'p' => vec![edit(EditCommand::Yank)],
+            'u' => vec![edit(EditCommand::Undo)],
+            'D' => vec![edit(EditCommand::DeleteToLineEnd)],
+            'C' => vec![edit(EditCommand::DeleteToLineEnd), insert_mode],
+            'i' => vec![insert_mode],
+            'a' => vec![movement(MovementCommand::MoveRightChar), insert_mode],
+            'I' => vec![movement(MovementCommand::MoveToLineStart), insert_mode],
+            'A' => vec![movement(MovementCommand::MoveToLineEnd), insert_mode],
+            'd' | 'c' => {
+                self.pending_operator = Some(ch);
+                vec![]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Events of the `d` and `c` operators followed by a motion, or doubled for the whole line
+fn operator_events(operator: char, motion: char) -> Vec<LineEditorEvent> {
+    let command = match motion {
+        'w' | 'e' => EditCommand::DeleteWordRight,
+        'b' => EditCommand::DeleteWordLeft,
+        '$' => EditCommand::DeleteToLineEnd,
+        '0' => EditCommand::DeleteToLineStart,
+        _ if motion == operator => EditCommand::DeleteToLineEnd,
+        _ => return vec![],
+    };
+
+    // A doubled operator clears the current line, not the whole multiline buffer
+    let mut events = vec![];
+    if motion == operator {
+        events.push(LineEditorEvent::Movement(vec![
+            MovementCommand::MoveToLineStart,
+        ]));
+    }
+    events.push(LineEditorEvent::Edit(vec![command]));
+    if operator == 'c' {
+        events.push(LineEditorEvent::SetViMode(ViMode::Insert));
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyEvent;
+
+    use super::*;
+
+    fn normal_mode() -> Vi {
+        let mut vi = Vi::default();
+        vi.set_mode(ViMode::Normal);
+        vi
+    }
+
+    fn press(vi: &mut Vi, code: KeyCode, modifiers: KeyModifiers) -> Option<Vec<LineEditorEvent>> {
+        vi.handle_key(&KeyCombination::from(KeyEvent::new(code, modifiers)))
+    }
+
+    fn type_char(vi: &mut Vi, ch: char) -> Option<Vec<LineEditorEvent>> {
+        press(vi, KeyCode::Char(ch), KeyModifiers::NONE)
+    }
+
+    fn edit(command: EditCommand) -> LineEditorEvent {
+        LineEditorEvent::Edit(vec![command])
+    }
+
+    fn movement(command: MovementCommand) -> LineEditorEvent {
+        LineEditorEvent::Movement(vec![command])
+    }
+
+    #[test]
+    fn normal_mode_motions() {
+        let mut vi = normal_mode();
+        for (ch, command) in [
+            ('h', MovementCommand::MoveLeftChar),
+            ('l', MovementCommand::MoveRightChar),
+            ('w', MovementCommand::MoveRightWord),
+            ('b', MovementCommand::MoveLeftWord),
+            ('e', MovementCommand::MoveRightWordEnd),
+            ('0', MovementCommand::MoveToLineStart),
+            ('$', MovementCommand::MoveToLineEnd),
+        ] {
+            assert_eq!(type_char(&mut vi, ch), Some(vec![movement(command)]));
+        }
+        assert_eq!(type_char(&mut vi, 'k'), Some(vec![LineEditorEvent::Up]));
+
+        // Unbound plain characters are swallowed, other keys use the Keybindings
+        assert_eq!(type_char(&mut vi, 'z'), Some(vec![]));
+        assert_eq!(press(&mut vi, KeyCode::Left, KeyModifiers::NONE), None);
+        assert_eq!(vi.mode(), ViMode::Normal);
+    }
+
+    #[test]
+    fn operators_with_motions() {
+        let mut vi = normal_mode();
+        assert_eq!(type_char(&mut vi, 'd'), Some(vec![]));
+        assert_eq!(
+            type_char(&mut vi, 'w'),
+            Some(vec![edit(EditCommand::DeleteWordRight)])
+        );
+
+        type_char(&mut vi, 'c');
+        assert_eq!(
+            type_char(&mut vi, 'b'),
+            Some(vec![
+                edit(EditCommand::DeleteWordLeft),
+                LineEditorEvent::SetViMode(ViMode::Insert),
+            ])
+        );
+    }
+
+    #[test]
+    fn doubled_operators_clear_the_current_line() {
+        let mut vi = normal_mode();
+        type_char(&mut vi, 'd');
+        assert_eq!(
+            type_char(&mut vi, 'd'),
+            Some(vec![
+                movement(MovementCommand::MoveToLineStart),
+                edit(EditCommand::DeleteToLineEnd),
+            ])
+        );
+
+        type_char(&mut vi, 'c');
+        assert_eq!(
+            type_char(&mut vi, 'c'),
+            Some(vec![
+                movement(MovementCommand::MoveToLineStart),
+                edit(EditCommand::DeleteToLineEnd),
+                LineEditorEvent::SetViMode(ViMode::Insert),
+            ])
+        );
+    }
+
+    #[test]
+    fn pending_operator_is_reset() {
+        let mut vi = normal_mode();
+
+        // An unknown motion cancels the operator
+        type_char(&mut vi, 'd');
+        assert_eq!(type_char(&mut vi, 'z'), Some(vec![]));
+        assert_eq!(
+            type_char(&mut vi, 'x'),
+            Some(vec![edit(EditCommand::DeleteRightChar)])
+        );
+
+        // A key that is not a plain character cancels the operator
+        type_char(&mut vi, 'd');
+        assert_eq!(press(&mut vi, KeyCode::Right, KeyModifiers::NONE), None);
+        assert_eq!(
+            type_char(&mut vi, 'w'),
+            Some(vec![movement(MovementCommand::MoveRightWord)])
+        );
+
+        type_char(&mut vi, 'd');
+        assert_eq!(
+            press(&mut vi, KeyCode::Char('w'), KeyModifiers::CONTROL),
+            None
+        );
+        assert_eq!(
+            type_char(&mut vi, 'w'),
+            Some(vec![movement(MovementCommand::MoveRightWord)])
+        );
+
+        // Switching the mode cancels the operator
+        type_char(&mut vi, 'd');
+        vi.set_mode(ViMode::Normal);
+        assert_eq!(
+            type_char(&mut vi, 'w'),
+            Some(vec![movement(MovementCommand::MoveRightWord)])
+        );
+    }
+
+    #[test]
+    fn switch_between_modes() {
+        let mut vi = Vi::default();
+        assert_eq!(vi.mode(), ViMode::Insert);
+        assert_eq!(type_char(&mut vi, 'h'), None);
+        assert_eq!(
+            press(&mut vi, KeyCode::Esc, KeyModifiers::NONE),
+            Some(vec![LineEditorEvent::SetViMode(ViMode::Normal)])
+        );
+
+        let mut vi = normal_mode();
+        let insert_mode = LineEditorEvent::SetViMode(ViMode::Insert);
+        assert_eq!(type_char(&mut vi, 'i'), Some(vec![insert_mode.clone()]));
+        assert_eq!(
+            type_char(&mut vi, 'A'),
+            Some(vec![
+                movement(MovementCommand::MoveToLineEnd),
+                insert_mode.clone()
+            ])
+        );
+        assert_eq!(
+            press(&mut vi, KeyCode::Char('I'), KeyModifiers::SHIFT),
+            Some(vec![
+                movement(MovementCommand::MoveToLineStart),
+                insert_mode
+            ])
+        );
+    }
+}
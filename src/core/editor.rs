@@ -2,10 +2,40 @@ use crate::event::MovementCommand;
 
 use super::event::EditCommand;
 use super::styled_buffer::StyledBuffer;
+use super::word_splitter::WordSplitter;
+
+/// Maximum number of entries kept in the kill ring
+const KILL_RING_MAX: usize = 60;
+
+/// Maximum number of undo units kept, the oldest unit is dropped when the stack is full
+const UNDO_STACK_MAX: usize = 100;
+
+/// Text inserted by the last yank, replaced by the next yank pop
+struct YankState {
+    start: usize,
+    end: usize,
+    index: usize,
+}
+
+/// Buffer text and cursor position saved by the undo and redo stacks
+struct EditorSnapshot {
+    text: String,
+    position: usize,
+}
 
 /// Wrapper for the Buffer to make it easy to run edit commands
 pub struct Editor {
     buffer: StyledBuffer,
+    word_splitter: WordSplitter,
+    undo_stack: Vec<EditorSnapshot>,
+    redo_stack: Vec<EditorSnapshot>,
+    is_inserting_chars: bool,
+    kill_ring: Vec<String>,
+    is_killing: bool,
+    yank: Option<YankState>,
+    max_length: Option<usize>,
+    is_input_truncated: bool,
+    is_secret: bool,
 }
 
 /// Create a new instance of [`Editor`]
@@ -13,6 +43,16 @@ impl Default for Editor {
     fn default() -> Self {
         Self {
             buffer: Default::default(),
+            word_splitter: WordSplitter::default(),
+            undo_stack: vec![],
+            redo_stack: vec![],
+            is_inserting_chars: false,
+            kill_ring: vec![],
+            is_killing: false,
+            yank: None,
+            max_length: None,
+            is_input_truncated: false,
+            is_secret: false,
         }
     }
 }
@@ -23,28 +63,523 @@ impl Editor {
         &mut self.buffer
     }
 
+    /// Get the current buffer text without styles
+    pub fn literal(&self) -> String {
+        self.buffer.literal()
+    }
+
+    /// Get the current cursor position in the buffer
+    pub fn position(&self) -> usize {
+        self.buffer.position()
+    }
+
+    /// Get the number of characters in the buffer
+    pub fn buffer_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Get the [`WordSplitter`] used by the word commands
+    pub fn word_splitter(&self) -> &WordSplitter {
+        &self.word_splitter
+    }
+
+    /// Set the [`WordSplitter`] used by the word commands
+    pub fn set_word_splitter(&mut self, word_splitter: WordSplitter) {
+        self.word_splitter = word_splitter;
+    }
+
+    /// Set the maximum number of characters the inserting commands can grow the buffer to,
+    /// inserted text is truncated to fit, None for no limit and None by default
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.max_length = max_length;
+    }
+
+    /// Get the maximum number of characters of the buffer, or None if there is no limit
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Returns true if the text inserted by the last edit command did not fit
+    /// in the maximum length and was truncated or dropped
+    pub fn is_input_truncated(&self) -> bool {
+        self.is_input_truncated
+    }
+
+    /// Returns true if the buffer can not grow anymore
+    pub fn is_at_max_length(&self) -> bool {
+        self.max_length
+            .is_some_and(|max_length| self.buffer.len() >= max_length)
+    }
+
+    /// Set if the buffer holds a secret like a password, the kills are not pushed on the
+    /// kill ring and no undo unit is recorded so the secret is not kept, false by default
+    pub fn set_secret(&mut self, is_secret: bool) {
+        self.is_secret = is_secret;
+    }
+
+    /// Returns true if the buffer holds a secret
+    pub fn is_secret(&self) -> bool {
+        self.is_secret
+    }
+
+    /// Push the text on the kill ring, the oldest entry is dropped when the ring is full
+    pub fn kill(&mut self, text: &str) {
+        if text.is_empty() || self.is_secret {
+            return;
+        }
+        if self.kill_ring.len() == KILL_RING_MAX {
+            self.kill_ring.remove(0);
+        }
+        self.kill_ring.push(text.to_string());
+    }
+
+    /// Remove all the kill ring entries
+    pub fn clear_kill_ring(&mut self) {
+        self.kill_ring.clear();
+        self.yank = None;
+    }
+
+    /// Get the kill ring entries from the oldest to the most recent
+    pub fn kill_ring(&self) -> &[String] {
+        &self.kill_ring
+    }
+
+    /// Forget the undo and redo history, so an undo can not restore the text of a previous line
+    pub fn clear_undo(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.is_inserting_chars = false;
+    }
+
+    /// Replace the buffer text and move the cursor in one operation that is recorded
+    /// as a single undo unit, the text is truncated to the maximum length and the cursor
+    /// is clamped to the end of the new text
+    pub fn set_state(&mut self, text: &str, cursor: usize) {
+        self.is_input_truncated = false;
+        let snapshot = self.snapshot();
+        self.buffer.clear();
+        let text = self.fit_length(text);
+        self.buffer.insert_string(text);
+        self.buffer
+            .set_position(usize::min(cursor, self.buffer.len()));
+        self.record_undo(snapshot, false);
+        self.is_killing = false;
+    }
+
     /// Apply [`EditCommand`] to the current buffer
+    ///
+    /// Every command that changes the text is one undo unit,
+    /// except consecutive [`EditCommand::InsertChar`] which are grouped into one unit
     pub fn run_edit_commands(&mut self, command: &EditCommand) {
+        self.is_input_truncated = false;
+        match command {
+            EditCommand::Undo => self.undo(),
+            EditCommand::Redo => self.redo(),
+            _ => {
+                let snapshot = self.snapshot();
+                self.apply_edit_command(command);
+                self.record_undo(snapshot, matches!(command, EditCommand::InsertChar(_)));
+            }
+        }
+
+        // Only a yank can be followed by a yank pop
+        if !matches!(command, EditCommand::Yank | EditCommand::YankPop) {
+            self.yank = None;
+        }
+        self.is_killing = command.is_kill();
+    }
+
+    /// Apply an [`EditCommand`] that changes the buffer
+    fn apply_edit_command(&mut self, command: &EditCommand) {
         match command {
-            EditCommand::InsertChar(c) => self.buffer.insert_char(*c),
-            EditCommand::InsertString(s) => self.buffer.insert_string(s),
+            EditCommand::InsertChar(c) => {
+                if self.is_at_max_length() {
+                    self.is_input_truncated = true;
+                } else {
+                    self.buffer.insert_char(*c);
+                }
+            }
+            EditCommand::InsertString(s) => {
+                let text = self.fit_length(s);
+                self.buffer.insert_string(text);
+            }
             EditCommand::DeleteLeftChar => self.buffer.delete_left_char(),
             EditCommand::DeleteRightChar => self.buffer.delete_right_char(),
             EditCommand::DeleteSpan(from, to) => self.buffer.delete_range(*from, *to),
+            EditCommand::DeleteWordLeft => {
+                let from = self.buffer.previous_word_start(&self.word_splitter);
+                self.kill_range(from, self.buffer.position());
+            }
+            EditCommand::DeleteWordRight => {
+                let to = self.buffer.next_word_end(&self.word_splitter);
+                self.kill_range(self.buffer.position(), to);
+            }
+            EditCommand::DeleteToLineEnd => {
+                self.kill_range(self.buffer.position(), self.buffer.line_end());
+            }
+            EditCommand::DeleteToLineStart => {
+                self.kill_range(self.buffer.line_start(), self.buffer.position());
+            }
+            EditCommand::Yank => {
+                if let Some(text) = self.kill_ring.last().cloned() {
+                    let start = self.buffer.position();
+                    let text = self.fit_length(&text);
+                    self.buffer.insert_string(text);
+                    self.yank = Some(YankState {
+                        start,
+                        end: self.buffer.position(),
+                        index: self.kill_ring.len() - 1,
+                    });
+                }
+            }
+            EditCommand::YankPop => {
+                if let Some(mut yank) = self.yank.take() {
+                    let len = self.kill_ring.len();
+                    yank.index = (yank.index + len - 1) % len;
+                    self.buffer.delete_range(yank.start, yank.end);
+                    let text = self.kill_ring[yank.index].clone();
+                    let text = self.fit_length(&text);
+                    self.buffer.insert_string(text);
+                    yank.end = self.buffer.position();
+                    self.yank = Some(yank);
+                }
+            }
+            EditCommand::TransposeChars => self.buffer.transpose_chars(),
             EditCommand::Clear => self.buffer.clear(),
+            EditCommand::Undo | EditCommand::Redo => {}
         }
     }
 
+    /// Take the start of the text that fits in the buffer without exceeding the maximum length
+    fn fit_length<'a>(&mut self, text: &'a str) -> &'a str {
+        let Some(max_length) = self.max_length else {
+            return text;
+        };
+
+        let remaining = max_length.saturating_sub(self.buffer.len());
+        match text.char_indices().nth(remaining) {
+            Some((end, _)) => {
+                self.is_input_truncated = true;
+                &text[..end]
+            }
+            None => text,
+        }
+    }
+
+    /// Delete the range and push the deleted text on the kill ring, the text of consecutive
+    /// kills is joined into one entry, before it for a kill to the left of the cursor
+    fn kill_range(&mut self, from: usize, to: usize) {
+        if let Some(text) = self.buffer.sub_string(from, to) {
+            match self.kill_ring.last_mut() {
+                Some(last) if self.is_killing && !self.is_secret => {
+                    if from < self.buffer.position() {
+                        last.insert_str(0, &text);
+                    } else {
+                        last.push_str(&text);
+                    }
+                }
+                _ => self.kill(&text),
+            }
+        }
+        self.buffer.delete_range(from, to);
+    }
+
+    /// Capture the current text and cursor position
+    fn snapshot(&self) -> EditorSnapshot {
+        EditorSnapshot {
+            text: self.buffer.literal(),
+            position: self.buffer.position(),
+        }
+    }
+
+    /// Restore the text and cursor position of a snapshot
+    fn restore(&mut self, snapshot: &EditorSnapshot) {
+        self.buffer.clear();
+        self.buffer.insert_string(&snapshot.text);
+        self.buffer.set_position(snapshot.position);
+    }
+
+    /// Push the state before an edit on the undo stack if the edit changed the text,
+    /// an inserted char following another one joins its undo unit
+    fn record_undo(&mut self, before: EditorSnapshot, is_insert_char: bool) {
+        if self.is_secret {
+            return;
+        }
+        if before.text != self.buffer.literal() {
+            if !(is_insert_char && self.is_inserting_chars) {
+                if self.undo_stack.len() == UNDO_STACK_MAX {
+                    self.undo_stack.remove(0);
+                }
+                self.undo_stack.push(before);
+            }
+            self.redo_stack.clear();
+        }
+        self.is_inserting_chars = is_insert_char;
+    }
+
+    /// Restore the state before the last undo unit
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.undo_stack.pop() {
+            self.redo_stack.push(self.snapshot());
+            self.restore(&snapshot);
+        }
+        self.is_inserting_chars = false;
+    }
+
+    /// Restore the state before the last undo
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            self.undo_stack.push(self.snapshot());
+            self.restore(&snapshot);
+        }
+        self.is_inserting_chars = false;
+    }
+
     /// Apply [`MovementCommand`] to the current buffer
     pub fn run_movement_commands(&mut self, command: &MovementCommand) {
+        // Moving the cursor ends the current group of inserted chars, the kills and the yank
+        self.is_inserting_chars = false;
+        self.is_killing = false;
+        self.yank = None;
         match command {
             MovementCommand::MoveToStart => self.buffer.move_to_start(),
             MovementCommand::MoveToEnd => self.buffer.move_to_end(),
+            MovementCommand::MoveToLineStart => self.buffer.move_to_line_start(),
+            MovementCommand::MoveToLineEnd => self.buffer.move_to_line_end(),
             MovementCommand::MoveLeftChar => self.buffer.move_char_left(),
             MovementCommand::MoveRightChar => self.buffer.move_char_right(),
-            MovementCommand::MoveLeftWord => self.buffer.move_word_left(),
-            MovementCommand::MoveRightWord => self.buffer.move_word_right(),
+            MovementCommand::MoveLeftWord => {
+                let position = self.buffer.previous_word_start(&self.word_splitter);
+                self.buffer.set_position(position);
+            }
+            MovementCommand::MoveRightWord => {
+                let position = self.buffer.next_word_start(&self.word_splitter);
+                self.buffer.set_position(position);
+            }
+            MovementCommand::MoveRightWordEnd => {
+                let position = self.buffer.next_word_end(&self.word_splitter);
+                self.buffer.set_position(position);
+            }
             MovementCommand::MoveToPosition(position) => self.buffer.set_position(*position),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_span_of_multibyte_characters() {
+        let mut editor = Editor::default();
+        editor.set_state("héllo wörld", 11);
+        editor.run_edit_commands(&EditCommand::DeleteSpan(6, 11));
+        assert_eq!(editor.literal(), "héllo ");
+        assert_eq!(editor.position(), 6);
+
+        editor.set_state("日本語 テキスト", 8);
+        editor.run_edit_commands(&EditCommand::DeleteSpan(0, 4));
+        assert_eq!(editor.literal(), "テキスト");
+    }
+
+    #[test]
+    fn replace_current_word_span_of_multibyte_characters() {
+        let mut editor = Editor::default();
+        editor.set_state("echo wö", 7);
+        let span = editor
+            .styled_buffer()
+            .current_word_span(&WordSplitter::Whitespace);
+        assert_eq!((span.start, span.end), (5, 7));
+
+        editor.run_edit_commands(&EditCommand::DeleteSpan(span.start, span.end));
+        editor.run_edit_commands(&EditCommand::InsertString("wörld".to_string()));
+        assert_eq!(editor.literal(), "echo wörld");
+    }
+
+    fn type_text(editor: &mut Editor, text: &str) {
+        for ch in text.chars() {
+            editor.run_edit_commands(&EditCommand::InsertChar(ch));
+        }
+    }
+
+    #[test]
+    fn set_state_fits_the_maximum_length() {
+        let mut editor = Editor::default();
+        editor.set_max_length(Some(4));
+        editor.set_state("héllo", 5);
+        assert_eq!((editor.literal().as_str(), editor.position()), ("héll", 4));
+        assert!(editor.is_input_truncated());
+
+        editor.set_state("abc", 1);
+        assert_eq!((editor.literal().as_str(), editor.position()), ("abc", 1));
+        assert!(!editor.is_input_truncated());
+    }
+
+    #[test]
+    fn undo_and_redo_edit_commands() {
+        let mut editor = Editor::default();
+        type_text(&mut editor, "ab");
+        editor.run_edit_commands(&EditCommand::DeleteLeftChar);
+        assert_eq!(editor.literal(), "a");
+
+        editor.run_edit_commands(&EditCommand::Undo);
+        assert_eq!((editor.literal().as_str(), editor.position()), ("ab", 2));
+        editor.run_edit_commands(&EditCommand::Undo);
+        assert_eq!((editor.literal().as_str(), editor.position()), ("", 0));
+        editor.run_edit_commands(&EditCommand::Undo);
+        assert_eq!(editor.literal(), "");
+
+        editor.run_edit_commands(&EditCommand::Redo);
+        assert_eq!(editor.literal(), "ab");
+        editor.run_edit_commands(&EditCommand::Redo);
+        assert_eq!(editor.literal(), "a");
+
+        // A new edit after an undo drops the redo history
+        editor.run_edit_commands(&EditCommand::Undo);
+        type_text(&mut editor, "c");
+        editor.run_edit_commands(&EditCommand::Redo);
+        assert_eq!(editor.literal(), "abc");
+    }
+
+    #[test]
+    fn undo_coalesces_inserted_chars_until_a_movement() {
+        let mut editor = Editor::default();
+        type_text(&mut editor, "ab");
+        editor.run_movement_commands(&MovementCommand::MoveLeftChar);
+        type_text(&mut editor, "cd");
+        assert_eq!(editor.literal(), "acdb");
+
+        editor.run_edit_commands(&EditCommand::Undo);
+        assert_eq!((editor.literal().as_str(), editor.position()), ("ab", 1));
+        editor.run_edit_commands(&EditCommand::Undo);
+        assert_eq!(editor.literal(), "");
+    }
+
+    #[test]
+    fn undo_stack_is_capped_and_cleared() {
+        let mut editor = Editor::default();
+        for _ in 0..UNDO_STACK_MAX + 10 {
+            type_text(&mut editor, "a");
+            editor.run_movement_commands(&MovementCommand::MoveToEnd);
+        }
+        for _ in 0..UNDO_STACK_MAX + 10 {
+            editor.run_edit_commands(&EditCommand::Undo);
+        }
+        assert_eq!(editor.buffer_len(), 10);
+
+        editor.clear_undo();
+        editor.run_edit_commands(&EditCommand::Undo);
+        editor.run_edit_commands(&EditCommand::Redo);
+        assert_eq!(editor.buffer_len(), 10);
+    }
+
+    #[test]
+    fn delete_to_line_bounds_of_multiline_buffer() {
+        let mut editor = Editor::default();
+        editor.set_state("ab\ncd", 1);
+        editor.run_edit_commands(&EditCommand::DeleteToLineEnd);
+        assert_eq!((editor.literal().as_str(), editor.position()), ("a\ncd", 1));
+
+        editor.set_state("ab\ncde", 5);
+        editor.run_edit_commands(&EditCommand::DeleteToLineStart);
+        assert_eq!((editor.literal().as_str(), editor.position()), ("ab\ne", 3));
+        assert_eq!(editor.kill_ring(), ["b", "cd"]);
+    }
+
+    #[test]
+    fn yank_and_yank_pop_cycle_the_kill_ring() {
+        let mut editor = Editor::default();
+        for text in ["one", "two", "three"] {
+            editor.kill(text);
+        }
+
+        editor.run_edit_commands(&EditCommand::Yank);
+        assert_eq!(editor.literal(), "three");
+        editor.run_edit_commands(&EditCommand::YankPop);
+        assert_eq!(editor.literal(), "two");
+        editor.run_edit_commands(&EditCommand::YankPop);
+        editor.run_edit_commands(&EditCommand::YankPop);
+        assert_eq!((editor.literal().as_str(), editor.position()), ("three", 5));
+
+        // A yank pop that does not follow a yank does nothing
+        editor.run_movement_commands(&MovementCommand::MoveToStart);
+        editor.run_edit_commands(&EditCommand::YankPop);
+        assert_eq!(editor.literal(), "three");
+    }
+
+    #[test]
+    fn kill_ring_drops_the_oldest_entry_when_full() {
+        let mut editor = Editor::default();
+        for i in 0..KILL_RING_MAX + 2 {
+            editor.kill(&i.to_string());
+        }
+        editor.kill("");
+
+        let kill_ring = editor.kill_ring();
+        assert_eq!(kill_ring.len(), KILL_RING_MAX);
+        assert_eq!(kill_ring[0], "2");
+        assert_eq!(
+            kill_ring[KILL_RING_MAX - 1],
+            (KILL_RING_MAX + 1).to_string()
+        );
+    }
+
+    #[test]
+    fn consecutive_kills_are_joined() {
+        let mut editor = Editor::default();
+        editor.set_state("one two three four", 8);
+        editor.run_edit_commands(&EditCommand::DeleteWordRight);
+        editor.run_edit_commands(&EditCommand::DeleteToLineEnd);
+        assert_eq!(editor.kill_ring(), ["three four"]);
+
+        editor.run_movement_commands(&MovementCommand::MoveToEnd);
+        editor.run_edit_commands(&EditCommand::DeleteWordLeft);
+        editor.run_edit_commands(&EditCommand::DeleteWordLeft);
+        assert_eq!(editor.kill_ring(), ["three four", "one two "]);
+
+        // Any other command starts a new entry
+        editor.run_edit_commands(&EditCommand::InsertString("a b".to_string()));
+        editor.run_edit_commands(&EditCommand::DeleteWordLeft);
+        editor.run_movement_commands(&MovementCommand::MoveToStart);
+        editor.run_edit_commands(&EditCommand::DeleteToLineEnd);
+        assert_eq!(editor.kill_ring(), ["three four", "one two ", "b", "a "]);
+    }
+
+    const DECOMPOSED: &str = "e\u{301}";
+    const FLAG: &str = "\u{1F1EB}\u{1F1F7}";
+    const FAMILY: &str = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+
+    #[test]
+    fn move_over_grapheme_clusters() {
+        for cluster in [DECOMPOSED, FLAG, FAMILY] {
+            let text = format!("a{cluster}b");
+            let cluster_len = cluster.chars().count();
+            let mut editor = Editor::default();
+            editor.set_state(&text, 1);
+
+            editor.run_movement_commands(&MovementCommand::MoveRightChar);
+            assert_eq!(editor.position(), 1 + cluster_len);
+            editor.run_movement_commands(&MovementCommand::MoveLeftChar);
+            assert_eq!(editor.position(), 1);
+        }
+    }
+
+    #[test]
+    fn delete_grapheme_clusters() {
+        for cluster in [DECOMPOSED, FLAG, FAMILY] {
+            let text = format!("a{cluster}b");
+            let cluster_len = cluster.chars().count();
+            let mut editor = Editor::default();
+
+            editor.set_state(&text, 1 + cluster_len);
+            editor.run_edit_commands(&EditCommand::DeleteLeftChar);
+            assert_eq!(editor.literal(), "ab");
+            assert_eq!(editor.position(), 1);
+
+            editor.set_state(&text, 1);
+            editor.run_edit_commands(&EditCommand::DeleteRightChar);
+            assert_eq!(editor.literal(), "ab");
+            assert_eq!(editor.position(), 1);
+        }
+    }
+}
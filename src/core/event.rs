@@ -1,7 +1,10 @@
+use crate::edit_mode::ViMode;
+
 /// Editing actions which can be mapped to key bindings.
 ///
 /// Executed by `Editor::run_edit_commands()`
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EditCommand {
     /// Insert a character at the current insertion point
     InsertChar(char),
@@ -15,15 +18,57 @@ pub enum EditCommand {
     /// Delete in-place from the current insertion point
     DeleteRightChar,
 
-    /// Delete in-place range
+    /// Delete in-place the half-open range of character indices
     DeleteSpan(usize, usize),
 
+    /// Delete from the insertion point back to the start of the previous word into the kill ring
+    DeleteWordLeft,
+
+    /// Delete from the insertion point to the end of the next word into the kill ring
+    DeleteWordRight,
+
+    /// Delete from the insertion point to the end of the line into the kill ring
+    DeleteToLineEnd,
+
+    /// Delete from the start of the line to the insertion point into the kill ring
+    DeleteToLineStart,
+
+    /// Insert the most recent kill ring entry at the insertion point
+    Yank,
+
+    /// Replace the text inserted by the previous yank with the older kill ring entry,
+    /// no-op if the previous command is not a yank
+    YankPop,
+
+    /// Swap the two characters around the insertion point and move after them
+    TransposeChars,
+
     /// Clear the current buffer
     Clear,
+
+    /// Undo the last edit
+    Undo,
+
+    /// Redo the last undone edit
+    Redo,
+}
+
+impl EditCommand {
+    /// Returns true if the command deletes text into the kill ring
+    pub fn is_kill(&self) -> bool {
+        matches!(
+            self,
+            EditCommand::DeleteWordLeft
+                | EditCommand::DeleteWordRight
+                | EditCommand::DeleteToLineEnd
+                | EditCommand::DeleteToLineStart
+        )
+    }
 }
 
 /// Movements actions which can be mapped to key bindings.
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MovementCommand {
     /// Move to the start of the buffer
     MoveToStart,
@@ -31,24 +76,34 @@ pub enum MovementCommand {
     /// Move to the end of the buffer
     MoveToEnd,
 
+    /// Move to the start of the current line, the start of the buffer if it has one line
+    MoveToLineStart,
+
+    /// Move to the end of the current line, the end of the buffer if it has one line
+    MoveToLineEnd,
+
     /// Move one character to the left
     MoveLeftChar,
 
     /// Move one character to the right
     MoveRightChar,
 
-    /// Move one word to the left
+    /// Move to the start of the previous word, words are defined by the editor WordSplitter
     MoveLeftWord,
 
-    /// Move one word to the right
+    /// Move to the start of the next word, words are defined by the editor WordSplitter
     MoveRightWord,
 
+    /// Move to the end of the current or next word, words are defined by the editor WordSplitter
+    MoveRightWordEnd,
+
     /// Move to position
     MoveToPosition(usize),
 }
 
 /// LineEditor supported actions.
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LineEditorEvent {
     /// No op event
     None,
@@ -62,6 +117,18 @@ pub enum LineEditorEvent {
     /// Handle unconditional submit event
     Submit,
 
+    /// Finish reading with [`LineEditorResult::Interrupted`], the buffer is cleared
+    /// unless it is disabled with `LineEditor::enable_clear_on_interrupt`
+    ///
+    /// [`LineEditorResult::Interrupted`]: crate::LineEditorResult::Interrupted
+    Interrupt,
+
+    /// Finish reading with [`LineEditorResult::EndTerminalSession`] if the buffer is empty,
+    /// otherwise delete like the Delete event
+    ///
+    /// [`LineEditorResult::EndTerminalSession`]: crate::LineEditorResult::EndTerminalSession
+    EndOfFile,
+
     /// Run these commands in the editor
     Edit(Vec<EditCommand>),
 
@@ -74,18 +141,37 @@ pub enum LineEditorEvent {
     /// Move down to the next line, if multiline, or down through the historic buffers
     Down,
 
+    /// Replace the buffer with the previous history entry that contains the line typed
+    /// before searching, repeat to find older entries and `Down` to go back
+    SearchHistory,
+
     /// Move right to the next column, completion entry, or complete hint
     Right,
 
     /// Move left to the next column, or completion entry
     Left,
 
+    /// Insert the next word of the rendered hint, or move right one word if there is no hint
+    AcceptHintWord,
+
     /// Select one character to the right
     SelectRight,
 
     /// Select one character to the left
     SelectLeft,
 
+    /// Extend the selection to the start of the previous word
+    SelectWordLeft,
+
+    /// Extend the selection to the start of the next word
+    SelectWordRight,
+
+    /// Extend the selection to the start of the buffer
+    SelectToStart,
+
+    /// Extend the selection to the end of the buffer
+    SelectToEnd,
+
     /// Select all buffer
     SelectAll,
 
@@ -104,6 +190,29 @@ pub enum LineEditorEvent {
     /// Delete char from the right or delete selected range
     Delete,
 
-    /// Show or Hide Auto Complete view depend on the state
+    /// Show the Auto Complete view, or focus the next suggestion if it is visible
     ToggleAutoComplete,
+
+    /// Focus the previous suggestion if the Auto Complete view is visible
+    AutoCompletePrevious,
+
+    /// Append the digit to the numeric argument, the number of times the next edit,
+    /// movement, delete, select, up or down event is repeated
+    NumericArgument(u8),
+
+    /// Clear the terminal and render the prompt and the current line at the top
+    ClearScreen,
+
+    /// Insert the next key literally, including control characters and bound keys
+    QuotedInsert,
+
+    /// Delete the pair characters around the selection or the cursor, like `(foo)` to `foo`
+    DeleteSurround,
+
+    /// Edit the buffer in the `$EDITOR` command and replace it with the saved text,
+    /// no-op if `$EDITOR` is not set or the command fails
+    EditInExternalEditor,
+
+    /// Switch the vi mode, has no effect in the Emacs edit mode
+    SetViMode(ViMode),
 }
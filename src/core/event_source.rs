@@ -0,0 +1,62 @@
+use std::collections::VecDeque;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::time::Duration;
+
+use crossterm::event;
+use crossterm::event::Event;
+
+/// The EventSource trait, Implementers of this trait provide the terminal events
+/// that are handled by the line editor
+pub trait EventSource {
+    /// Returns true if an event is available within the timeout
+    fn poll(&mut self, timeout: Duration) -> Result<bool>;
+
+    /// Wait for the next event
+    fn read(&mut self) -> Result<Event>;
+}
+
+/// EventSource that reads the events from the terminal using crossterm, the default
+#[derive(Default)]
+pub struct CrosstermEventSource;
+
+impl EventSource for CrosstermEventSource {
+    fn poll(&mut self, timeout: Duration) -> Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn read(&mut self) -> Result<Event> {
+        event::read()
+    }
+}
+
+/// EventSource that yields a predefined list of events, for example to drive
+/// the line editor with synthetic key presses in tests
+///
+/// Reading after the last event returns an [`ErrorKind::UnexpectedEof`] error
+#[derive(Default)]
+pub struct ScriptedEventSource {
+    events: VecDeque<Event>,
+}
+
+impl ScriptedEventSource {
+    /// Create instance of ScriptedEventSource with the events in the order they are read
+    pub fn new(events: Vec<Event>) -> Self {
+        ScriptedEventSource {
+            events: events.into(),
+        }
+    }
+}
+
+impl EventSource for ScriptedEventSource {
+    fn poll(&mut self, _timeout: Duration) -> Result<bool> {
+        Ok(!self.events.is_empty())
+    }
+
+    fn read(&mut self) -> Result<Event> {
+        self.events
+            .pop_front()
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "No more scripted events"))
+    }
+}
@@ -9,6 +9,10 @@
 ///
 /// // To Make rules that accept any Text but not accept Punctuation
 /// InputFilter::Options(vec![Box::new(InputFilter::Alphabetic), Box::new(Not(Box::new(InputFilter::Punctuation)))])
+///
+/// // To Make rules that accept only the characters of a set
+/// let allowed = vec!['+', '-', '*', '/'];
+/// InputFilter::Closure(Box::new(move |ch| allowed.contains(&ch)))
 /// ```
 ///
 pub enum InputFilter {
@@ -32,6 +36,13 @@ pub enum InputFilter {
     Options(Vec<Box<InputFilter>>),
     /// User defined input filter function
     Custom(fn(char) -> bool),
+    /// User defined input filter closure, it can capture state like a set of allowed characters
+    Closure(Box<dyn Fn(char) -> bool>),
+    /// Valid if the character alone matches the regex, for example `[a-zA-Z0-9_]`
+    ///
+    /// Pasted text is checked one character at a time in the same way, see [`PasteFilter`]
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
 }
 
 /// Input Filter function that returns true if character is matching the rules of the given InputFilter
@@ -54,5 +65,126 @@ pub fn filter_input(ch: char, input_filter: &InputFilter) -> bool {
             false
         }
         InputFilter::Custom(function) => function(ch),
+        InputFilter::Closure(closure) => closure(ch),
+        #[cfg(feature = "regex")]
+        InputFilter::Regex(regex) => regex.is_match(ch.encode_utf8(&mut [0; 4])),
+    }
+}
+
+/// How pasted text is checked against the InputFilter
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PasteFilter {
+    /// Insert the pasted text without the characters that are not matching, the default
+    DropInvalid,
+    /// Ignore the whole pasted text if one of the characters is not matching
+    RejectInvalid,
+}
+
+/// Input Filter function that returns the pasted text to insert, or None if nothing should be inserted
+pub fn filter_paste(text: &str, input_filter: &InputFilter, mode: PasteFilter) -> Option<String> {
+    let filtered: String = text
+        .chars()
+        .filter(|ch| filter_input(*ch, input_filter))
+        .collect();
+    if filtered.is_empty() {
+        return None;
+    }
+
+    if mode == PasteFilter::RejectInvalid && filtered.len() != text.len() {
+        return None;
+    }
+
+    Some(filtered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIXED: &str = "aB3 f!é\t9";
+
+    fn drop_invalid(input_filter: InputFilter) -> Option<String> {
+        filter_paste(MIXED, &input_filter, PasteFilter::DropInvalid)
+    }
+
+    #[test]
+    fn drop_invalid_pasted_characters() {
+        assert_eq!(
+            drop_invalid(InputFilter::Alphabetic).as_deref(),
+            Some("aBfé")
+        );
+        assert_eq!(
+            drop_invalid(InputFilter::AlphaNumeric).as_deref(),
+            Some("aB3fé9")
+        );
+        assert_eq!(drop_invalid(InputFilter::Digit).as_deref(), Some("39"));
+        assert_eq!(drop_invalid(InputFilter::Text).as_deref(), Some(MIXED));
+        assert_eq!(
+            drop_invalid(InputFilter::HexDigit).as_deref(),
+            Some("aB3f9")
+        );
+        assert_eq!(
+            drop_invalid(InputFilter::Whitespace).as_deref(),
+            Some(" \t")
+        );
+        assert_eq!(drop_invalid(InputFilter::Punctuation).as_deref(), Some("!"));
+        assert_eq!(
+            drop_invalid(InputFilter::Not(Box::new(InputFilter::AlphaNumeric))).as_deref(),
+            Some(" !\t")
+        );
+        assert_eq!(
+            drop_invalid(InputFilter::Options(vec![
+                Box::new(InputFilter::Digit),
+                Box::new(InputFilter::Punctuation),
+            ]))
+            .as_deref(),
+            Some("3!9")
+        );
+        assert_eq!(
+            drop_invalid(InputFilter::Custom(|ch| ch.is_ascii_uppercase())).as_deref(),
+            Some("B")
+        );
+        let allowed = ['a', 'é'];
+        assert_eq!(
+            drop_invalid(InputFilter::Closure(Box::new(
+                move |ch| allowed.contains(&ch)
+            )))
+            .as_deref(),
+            Some("aé")
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn drop_invalid_pasted_characters_with_regex() {
+        let regex = regex::Regex::new("[a-z]").unwrap();
+        assert_eq!(
+            drop_invalid(InputFilter::Regex(regex)).as_deref(),
+            Some("af")
+        );
+    }
+
+    #[test]
+    fn reject_paste_with_invalid_characters() {
+        let digit = InputFilter::Digit;
+        assert_eq!(
+            filter_paste(MIXED, &digit, PasteFilter::RejectInvalid),
+            None
+        );
+        assert_eq!(
+            filter_paste("2024", &digit, PasteFilter::RejectInvalid).as_deref(),
+            Some("2024")
+        );
+        assert_eq!(
+            filter_paste(MIXED, &InputFilter::Text, PasteFilter::RejectInvalid).as_deref(),
+            Some(MIXED)
+        );
+    }
+
+    #[test]
+    fn paste_without_valid_characters() {
+        let digit = InputFilter::Digit;
+        assert_eq!(filter_paste("abc", &digit, PasteFilter::DropInvalid), None);
+        assert_eq!(filter_paste("", &digit, PasteFilter::DropInvalid), None);
     }
 }
@@ -1,11 +1,19 @@
+#[cfg(feature = "serde")]
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::fmt;
 use std::hash::Hash;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::str::FromStr;
+use std::time::Duration;
 
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
 
+use crate::event::EditCommand;
 use crate::event::MovementCommand;
 
 use super::event::LineEditorEvent;
@@ -29,10 +37,243 @@ impl From<KeyEvent> for KeyCombination {
     }
 }
 
+impl KeyCombination {
+    /// Return the character this key inserts when taken literally, or None if it has no
+    /// character, for example `CTRL + a` is `\x01` and `Tab` is `\t`
+    pub fn literal_char(&self) -> Option<char> {
+        match self.key_code {
+            KeyCode::Char(ch) if self.modifier.contains(KeyModifiers::CONTROL) => {
+                if ch.is_ascii_alphabetic() || "@[\\]^_".contains(ch) {
+                    Some((ch.to_ascii_uppercase() as u8 & 0x1f) as char)
+                } else {
+                    None
+                }
+            }
+            KeyCode::Char(ch) => Some(ch),
+            KeyCode::Tab | KeyCode::BackTab => Some('\t'),
+            KeyCode::Enter => Some('\r'),
+            KeyCode::Esc => Some('\x1b'),
+            KeyCode::Backspace => Some('\x7f'),
+            _ => None,
+        }
+    }
+}
+
+/// Format the key combination like `ctrl+w`, `alt+shift+left` or `release:esc`,
+/// the format accepted by [`KeyCombination::from_str`]
+impl fmt::Display for KeyCombination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.key_kind {
+            KeyEventKind::Press => {}
+            KeyEventKind::Repeat => write!(f, "repeat:")?,
+            KeyEventKind::Release => write!(f, "release:")?,
+        }
+
+        for (name, modifier) in MODIFIER_NAMES {
+            if self.modifier.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+
+        match key_code_name(self.key_code) {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{}", format!("{:?}", self.key_code).to_lowercase()),
+        }
+    }
+}
+
+/// Parse a key combination like `ctrl+w`, the modifiers are `ctrl`, `alt`, `shift`, `super`,
+/// `hyper` and `meta`, the key is one character or a key name like `enter`, `space` or `f1`,
+/// the combination is a key press unless it starts with `repeat:` or `release:`
+impl FromStr for KeyCombination {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid key combination `{}`, {}", text, reason),
+            )
+        };
+
+        let (key_kind, combination) = if let Some(rest) = text.strip_prefix("repeat:") {
+            (KeyEventKind::Repeat, rest)
+        } else if let Some(rest) = text.strip_prefix("release:") {
+            (KeyEventKind::Release, rest)
+        } else {
+            (KeyEventKind::Press, text)
+        };
+
+        // The last `+` of `ctrl++` is the key itself
+        let (modifiers, key) = match combination.strip_suffix('+') {
+            Some(rest) if rest.is_empty() || rest.ends_with('+') => {
+                (rest.strip_suffix('+').unwrap_or(rest), "+")
+            }
+            _ => combination.rsplit_once('+').unwrap_or(("", combination)),
+        };
+
+        let mut modifier = KeyModifiers::NONE;
+        for name in modifiers.split('+').filter(|name| !name.is_empty()) {
+            let name = name.to_lowercase();
+            let Some((_, flag)) = MODIFIER_NAMES.iter().find(|(n, _)| *n == name) else {
+                return Err(invalid(&format!("unknown modifier `{}`", name)));
+            };
+            modifier |= *flag;
+        }
+
+        if key.is_empty() {
+            return Err(invalid("missing key"));
+        }
+
+        let Some(key_code) = parse_key_code(key) else {
+            return Err(invalid(&format!("unknown key `{}`", key)));
+        };
+
+        Ok(KeyCombination {
+            key_kind,
+            modifier,
+            key_code,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for KeyCombination {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for KeyCombination {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Names of the modifiers in the order they are formatted
+const MODIFIER_NAMES: [(&str, KeyModifiers); 6] = [
+    ("ctrl", KeyModifiers::CONTROL),
+    ("alt", KeyModifiers::ALT),
+    ("shift", KeyModifiers::SHIFT),
+    ("super", KeyModifiers::SUPER),
+    ("hyper", KeyModifiers::HYPER),
+    ("meta", KeyModifiers::META),
+];
+
+/// Name of the key in the key combination format, or None if the key has no name
+fn key_code_name(key_code: KeyCode) -> Option<String> {
+    let name = match key_code {
+        KeyCode::Char(' ') => "space",
+        KeyCode::Char(ch) => return Some(ch.to_string()),
+        KeyCode::F(number) => return Some(format!("f{}", number)),
+        KeyCode::Enter => "enter",
+        KeyCode::Esc => "esc",
+        KeyCode::Tab => "tab",
+        KeyCode::BackTab => "backtab",
+        KeyCode::Backspace => "backspace",
+        KeyCode::Delete => "delete",
+        KeyCode::Insert => "insert",
+        KeyCode::Home => "home",
+        KeyCode::End => "end",
+        KeyCode::PageUp => "pageup",
+        KeyCode::PageDown => "pagedown",
+        KeyCode::Up => "up",
+        KeyCode::Down => "down",
+        KeyCode::Left => "left",
+        KeyCode::Right => "right",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Parse one character or a key name of the key combination format
+fn parse_key_code(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(ch));
+    }
+
+    let name = key.to_lowercase();
+    if let Some(number) = name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return Some(KeyCode::F(number));
+    }
+
+    let key_code = match name.as_str() {
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        _ => return None,
+    };
+    Some(key_code)
+}
+
+/// Result of looking up a sequence of key combinations
+pub enum KeySequenceMatch {
+    /// The sequence is bound to an event
+    Complete(LineEditorEvent),
+    /// The sequence is the start of one or more longer bound sequences
+    Prefix,
+    /// The sequence is not bound
+    NoMatch,
+}
+
+/// Trie node of the key sequence bindings
+#[derive(Default)]
+struct KeySequenceNode {
+    event: Option<LineEditorEvent>,
+    children: HashMap<KeyCombination, KeySequenceNode>,
+}
+
+/// A key combination or sequence that was registered again with a different event,
+/// the new event replaced the previous one
+#[derive(Clone, Debug)]
+pub struct KeybindingConflict {
+    /// The key combination, or the key combinations of a sequence
+    pub keys: Vec<KeyCombination>,
+    /// The event that was replaced
+    pub previous: LineEditorEvent,
+    /// The event that is bound now
+    pub event: LineEditorEvent,
+}
+
+/// Keybindings in the config file format, the sequences are key combinations
+/// separated by spaces like `"ctrl+x ctrl+e"`
+#[cfg(feature = "serde")]
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct KeybindingsConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sequence_timeout_ms: Option<u64>,
+    #[serde(default)]
+    bindings: BTreeMap<String, LineEditorEvent>,
+    #[serde(default)]
+    sequences: BTreeMap<String, LineEditorEvent>,
+}
+
 /// Map of keybindings and [`LineEditorEvent`]
 pub struct Keybindings {
     /// Defines a keybinding for a reedline event
     pub bindings: HashMap<KeyCombination, LineEditorEvent>,
+    /// Defines the multi key sequences like `CTRL + X` then `CTRL + S`
+    sequences: KeySequenceNode,
+    /// Maximum time to wait for the next key of an incomplete sequence
+    sequence_timeout: Duration,
+    /// Registrations that replaced a different event
+    conflicts: Vec<KeybindingConflict>,
 }
 
 /// Create a new instance of [`Keybindings`]
@@ -40,14 +281,97 @@ impl Default for Keybindings {
     fn default() -> Self {
         Keybindings {
             bindings: HashMap::new(),
+            sequences: KeySequenceNode::default(),
+            sequence_timeout: Duration::from_millis(1000),
+            conflicts: vec![],
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Keybindings {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut config = KeybindingsConfig {
+            sequence_timeout_ms: Some(self.sequence_timeout.as_millis() as u64),
+            ..Default::default()
+        };
+        for (key_combination, event) in &self.bindings {
+            config
+                .bindings
+                .insert(key_combination.to_string(), event.clone());
+        }
+
+        for (sequence, event) in self.sequence_bindings() {
+            let sequence: Vec<String> = sequence.iter().map(|key| key.to_string()).collect();
+            config.sequences.insert(sequence.join(" "), event);
+        }
+        config.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Keybindings {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+
+        let config = KeybindingsConfig::deserialize(deserializer)?;
+        let mut keybindings = Keybindings::default();
+        if let Some(timeout) = config.sequence_timeout_ms {
+            keybindings.set_sequence_timeout(Duration::from_millis(timeout));
+        }
+
+        for (key_combination, event) in config.bindings {
+            let key_combination = key_combination.parse().map_err(D::Error::custom)?;
+            keybindings.register_binding(key_combination, event);
+        }
+
+        for (sequence, event) in config.sequences {
+            let sequence = sequence
+                .split_whitespace()
+                .map(KeyCombination::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(D::Error::custom)?;
+            if sequence.is_empty() {
+                return Err(D::Error::custom("Invalid key sequence, missing key"));
+            }
+            keybindings.register_sequence_binding(sequence, event);
+        }
+        Ok(keybindings)
+    }
+}
+
+impl KeySequenceNode {
+    /// Collect the sequences bound under this node, prefixed with the sequence of the node
+    fn collect_sequences(
+        &self,
+        sequence: &mut Vec<KeyCombination>,
+        sequences: &mut Vec<(Vec<KeyCombination>, LineEditorEvent)>,
+    ) {
+        if let Some(event) = &self.event {
+            sequences.push((sequence.clone(), event.clone()));
+        }
+
+        for (key_combination, child) in &self.children {
+            sequence.push(key_combination.clone());
+            child.collect_sequences(sequence, sequences);
+            sequence.pop();
         }
     }
 }
 
 impl Keybindings {
     /// Register an [`LineEditorEvent`] for specific key combination
+    ///
+    /// Replacing a different event is recorded in [`Keybindings::conflicts`]
     pub fn register_binding(&mut self, key_combination: KeyCombination, event: LineEditorEvent) {
-        self.bindings.insert(key_combination, event);
+        let previous = self.bindings.insert(key_combination.clone(), event.clone());
+        if let Some(previous) = previous.filter(|previous| *previous != event) {
+            self.conflicts.push(KeybindingConflict {
+                keys: vec![key_combination],
+                previous,
+                event,
+            });
+        }
     }
 
     /// Find a keybinding based on the modifier and keycode
@@ -55,14 +379,203 @@ impl Keybindings {
         self.bindings.get(&key_combination).cloned()
     }
 
+    /// Register an [`LineEditorEvent`] for a sequence of key combinations,
+    /// the line editor waits for the next key after a prefix of a registered sequence
+    pub fn register_sequence_binding(
+        &mut self,
+        sequence: Vec<KeyCombination>,
+        event: LineEditorEvent,
+    ) {
+        let mut node = &mut self.sequences;
+        for key_combination in sequence.iter().cloned() {
+            node = node.children.entry(key_combination).or_default();
+        }
+
+        let previous = node.event.replace(event.clone());
+        if let Some(previous) = previous.filter(|previous| *previous != event) {
+            self.conflicts.push(KeybindingConflict {
+                keys: sequence,
+                previous,
+                event,
+            });
+        }
+    }
+
+    /// Get the registered key sequences and their events
+    pub fn sequence_bindings(&self) -> Vec<(Vec<KeyCombination>, LineEditorEvent)> {
+        let mut sequences = vec![];
+        self.sequences
+            .collect_sequences(&mut vec![], &mut sequences);
+        sequences
+    }
+
+    /// Register all the bindings and sequences of the other keybindings on top of these,
+    /// for example a user config on top of a preset
+    ///
+    /// The replaced events are recorded in [`Keybindings::conflicts`]
+    pub fn merge(&mut self, other: Keybindings) {
+        let sequences = other.sequence_bindings();
+        for (key_combination, event) in other.bindings {
+            self.register_binding(key_combination, event);
+        }
+
+        for (sequence, event) in sequences {
+            self.register_sequence_binding(sequence, event);
+        }
+    }
+
+    /// Get the registrations that replaced a different event, in registration order
+    pub fn conflicts(&self) -> &[KeybindingConflict] {
+        &self.conflicts
+    }
+
+    /// Forget the recorded conflicts, for example after warning the user about them
+    pub fn clear_conflicts(&mut self) {
+        self.conflicts.clear();
+    }
+
+    /// Find a key sequence binding, or report if the sequence is a prefix of longer ones
+    pub fn find_sequence_binding(&self, sequence: &[KeyCombination]) -> KeySequenceMatch {
+        let mut node = &self.sequences;
+        for key_combination in sequence {
+            match node.children.get(key_combination) {
+                Some(child) => node = child,
+                None => return KeySequenceMatch::NoMatch,
+            }
+        }
+
+        if let Some(event) = &node.event {
+            return KeySequenceMatch::Complete(event.clone());
+        }
+
+        if node.children.is_empty() {
+            KeySequenceMatch::NoMatch
+        } else {
+            KeySequenceMatch::Prefix
+        }
+    }
+
+    /// Set the maximum time to wait for the next key of an incomplete sequence,
+    /// after that the sequence is aborted, or the binding of a one key prefix is used
+    pub fn set_sequence_timeout(&mut self, timeout: Duration) {
+        self.sequence_timeout = timeout;
+    }
+
+    /// Get the maximum time to wait for the next key of an incomplete sequence
+    pub fn sequence_timeout(&self) -> Duration {
+        self.sequence_timeout
+    }
+
+    /// Load the keybindings from a TOML config, the `bindings` table maps key combinations
+    /// like `"ctrl+w"` to events, the `sequences` table maps key combinations separated
+    /// by spaces like `"ctrl+x ctrl+e"` to events and the optional `sequence_timeout_ms`
+    /// is the [`Keybindings::sequence_timeout`] in milliseconds
+    ///
+    /// ```toml
+    /// sequence_timeout_ms = 500
+    ///
+    /// [bindings]
+    /// "ctrl+l" = "ClearScreen"
+    /// "ctrl+w" = { Edit = ["DeleteWordLeft"] }
+    /// ```
+    ///
+    /// Returns an error that describes the invalid key combination or event
+    #[cfg(feature = "serde")]
+    pub fn from_toml(config: &str) -> std::io::Result<Keybindings> {
+        toml::from_str(config).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+    }
+
+    /// Save the keybindings and the sequence timeout in the TOML config format
+    /// of [`Keybindings::from_toml`]
+    #[cfg(feature = "serde")]
+    pub fn to_toml(&self) -> std::io::Result<String> {
+        toml::to_string(self).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+    }
+
     /// Get assigned keybindings
     pub fn keybindings(&self) -> &HashMap<KeyCombination, LineEditorEvent> {
         &self.bindings
     }
 
+    /// Create the Emacs preset, the common control, navigation, edit and selection bindings
+    /// with the kill ring and numeric argument bindings, and the Emacs movements
+    ///
+    /// `CTRL + A` and `CTRL + E` to move to the start and the end of the line
+    /// `CTRL + F` and `CTRL + B` to move one character right and left
+    /// `ALT + F` and `ALT + B` to move one word right and left
+    /// `CTRL + W`, `ALT + D`, `CTRL + K`, `CTRL + U` to kill words and lines
+    /// `CTRL + Y` and `ALT + Y` to yank, `CTRL + T` to transpose
+    /// `CTRL + R` to search the history
+    /// `CTRL + X` then `CTRL + E` to edit the buffer in the `$EDITOR` command
+    ///
+    /// `CTRL + A` replaces the select all binding
+    pub fn emacs() -> Self {
+        let mut keybindings = Keybindings::default();
+        keybindings.register_common_control_bindings();
+        keybindings.register_common_navigation_bindings();
+        keybindings.register_common_edit_bindings();
+        keybindings.register_common_selection_bindings();
+        keybindings.register_kill_ring_bindings();
+        keybindings.register_numeric_argument_bindings();
+        keybindings.register_external_editor_bindings();
+
+        let emacs_bindings = [
+            (
+                KeyModifiers::CONTROL,
+                'a',
+                LineEditorEvent::Movement(vec![MovementCommand::MoveToLineStart]),
+            ),
+            (
+                KeyModifiers::CONTROL,
+                'e',
+                LineEditorEvent::Movement(vec![MovementCommand::MoveToLineEnd]),
+            ),
+            (
+                KeyModifiers::CONTROL,
+                'f',
+                LineEditorEvent::Movement(vec![MovementCommand::MoveRightChar]),
+            ),
+            (
+                KeyModifiers::CONTROL,
+                'b',
+                LineEditorEvent::Movement(vec![MovementCommand::MoveLeftChar]),
+            ),
+            (
+                KeyModifiers::ALT,
+                'f',
+                LineEditorEvent::Movement(vec![MovementCommand::MoveRightWord]),
+            ),
+            (
+                KeyModifiers::ALT,
+                'b',
+                LineEditorEvent::Movement(vec![MovementCommand::MoveLeftWord]),
+            ),
+            (KeyModifiers::CONTROL, 'r', LineEditorEvent::SearchHistory),
+        ];
+
+        for (modifier, ch, event) in emacs_bindings {
+            keybindings.register_binding(
+                KeyCombination {
+                    key_kind: KeyEventKind::Press,
+                    modifier,
+                    key_code: KeyCode::Char(ch),
+                },
+                event,
+            );
+        }
+
+        // The preset replaces some of the common bindings on purpose
+        keybindings.clear_conflicts();
+        keybindings
+    }
+
     /// Register basic functionality to Control
     ///
     /// `Enter`, `Esc`
+    /// `CTRL + L` to clear the screen
+    /// `CTRL + C` to interrupt the line, read_line returns `Interrupted` and the buffer is cleared
+    /// `CTRL + D` to end the session when the buffer is empty, read_line returns
+    /// `EndTerminalSession`, otherwise it deletes the character on the right like `Delete`
     pub fn register_common_control_bindings(&mut self) {
         self.register_binding(
             KeyCombination {
@@ -81,13 +594,45 @@ impl Keybindings {
             },
             LineEditorEvent::Esc,
         );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('l'),
+            },
+            LineEditorEvent::ClearScreen,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('c'),
+            },
+            LineEditorEvent::Interrupt,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('d'),
+            },
+            LineEditorEvent::EndOfFile,
+        );
     }
 
     /// Register basic functionality to Navigation
     ///
     /// `Up`, `Down`, `Right`, `Left` Arrow keys
     /// `CTRL + Right`, `CTRL + Left`
-    /// `Home`, `End`
+    /// `Home`, `End`, `CTRL + A` and `CTRL + E` to move to the start and the end of the current line
+    /// `CTRL + Home`, `CTRL + End` to move to the start and the end of the buffer
+    /// `ALT + Right` to accept the next word of the hint
+    ///
+    /// The common selection bindings also use `CTRL + A` to select all, the set registered last
+    /// keeps it and the replacement is recorded in [`Keybindings::conflicts`]
     pub fn register_common_navigation_bindings(&mut self) {
         self.register_binding(
             KeyCombination {
@@ -131,7 +676,7 @@ impl Keybindings {
                 modifier: KeyModifiers::NONE,
                 key_code: KeyCode::Home,
             },
-            LineEditorEvent::Movement(vec![MovementCommand::MoveToStart]),
+            LineEditorEvent::Movement(vec![MovementCommand::MoveToLineStart]),
         );
 
         self.register_binding(
@@ -140,9 +685,45 @@ impl Keybindings {
                 modifier: KeyModifiers::NONE,
                 key_code: KeyCode::End,
             },
+            LineEditorEvent::Movement(vec![MovementCommand::MoveToLineEnd]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Home,
+            },
+            LineEditorEvent::Movement(vec![MovementCommand::MoveToStart]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::End,
+            },
             LineEditorEvent::Movement(vec![MovementCommand::MoveToEnd]),
         );
 
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('a'),
+            },
+            LineEditorEvent::Movement(vec![MovementCommand::MoveToLineStart]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('e'),
+            },
+            LineEditorEvent::Movement(vec![MovementCommand::MoveToLineEnd]),
+        );
+
         self.register_binding(
             KeyCombination {
                 key_kind: KeyEventKind::Press,
@@ -160,11 +741,24 @@ impl Keybindings {
             },
             LineEditorEvent::Movement(vec![MovementCommand::MoveRightWord]),
         );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::ALT,
+                key_code: KeyCode::Right,
+            },
+            LineEditorEvent::AcceptHintWord,
+        );
     }
 
     /// Register basic functionality to edit
     ///
     /// `Delete`, `Backspace` and the basic variants do delete words
+    /// `CTRL + W` and `ALT + D` to delete the previous and next word
+    /// `CTRL + K` and `CTRL + U` to delete to the end and start of the line
+    /// `CTRL + T` to transpose the characters around the cursor
+    /// `CTRL + Z` to undo and `CTRL + Y` to redo
     pub fn register_common_edit_bindings(&mut self) {
         self.register_binding(
             KeyCombination {
@@ -183,11 +777,138 @@ impl Keybindings {
             },
             LineEditorEvent::Delete,
         );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('w'),
+            },
+            LineEditorEvent::Edit(vec![EditCommand::DeleteWordLeft]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::ALT,
+                key_code: KeyCode::Char('d'),
+            },
+            LineEditorEvent::Edit(vec![EditCommand::DeleteWordRight]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('k'),
+            },
+            LineEditorEvent::Edit(vec![EditCommand::DeleteToLineEnd]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('u'),
+            },
+            LineEditorEvent::Edit(vec![EditCommand::DeleteToLineStart]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('t'),
+            },
+            LineEditorEvent::Edit(vec![EditCommand::TransposeChars]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('z'),
+            },
+            LineEditorEvent::Edit(vec![EditCommand::Undo]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('y'),
+            },
+            LineEditorEvent::Edit(vec![EditCommand::Redo]),
+        );
+    }
+
+    /// Register the external editor functionality
+    ///
+    /// `CTRL + X` then `CTRL + E` to edit the buffer in the `$EDITOR` command
+    pub fn register_external_editor_bindings(&mut self) {
+        self.register_sequence_binding(
+            vec![
+                KeyCombination {
+                    key_kind: KeyEventKind::Press,
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('x'),
+                },
+                KeyCombination {
+                    key_kind: KeyEventKind::Press,
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('e'),
+                },
+            ],
+            LineEditorEvent::EditInExternalEditor,
+        );
+    }
+
+    /// Register the Emacs kill ring functionality
+    ///
+    /// `CTRL + Y` to yank the last killed text, it replaces the redo binding
+    /// `ALT + Y` to replace the yanked text with the previous killed text
+    pub fn register_kill_ring_bindings(&mut self) {
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('y'),
+            },
+            LineEditorEvent::Edit(vec![EditCommand::Yank]),
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::ALT,
+                key_code: KeyCode::Char('y'),
+            },
+            LineEditorEvent::Edit(vec![EditCommand::YankPop]),
+        );
+    }
+
+    /// Register the Emacs numeric argument functionality
+    ///
+    /// `ALT + 0` to `ALT + 9` to repeat the next edit or movement, for example `ALT + 5`
+    /// followed by `Delete` deletes five characters
+    pub fn register_numeric_argument_bindings(&mut self) {
+        for digit in 0..=9 {
+            self.register_binding(
+                KeyCombination {
+                    key_kind: KeyEventKind::Press,
+                    modifier: KeyModifiers::ALT,
+                    key_code: KeyCode::Char(char::from(b'0' + digit)),
+                },
+                LineEditorEvent::NumericArgument(digit),
+            );
+        }
     }
 
     /// Register basic functionality to selection
     ///
-    /// Select right and left and select all
+    /// Select right and left by character or by word with `CTRL`,
+    /// select to the start and the end and select all with `CTRL + A`, it replaces
+    /// the line start binding of the common navigation bindings registered before
     pub fn register_common_selection_bindings(&mut self) {
         self.register_binding(
             KeyCombination {
@@ -207,6 +928,42 @@ impl Keybindings {
             LineEditorEvent::SelectRight,
         );
 
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+                key_code: KeyCode::Left,
+            },
+            LineEditorEvent::SelectWordLeft,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+                key_code: KeyCode::Right,
+            },
+            LineEditorEvent::SelectWordRight,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::SHIFT,
+                key_code: KeyCode::Home,
+            },
+            LineEditorEvent::SelectToStart,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::SHIFT,
+                key_code: KeyCode::End,
+            },
+            LineEditorEvent::SelectToEnd,
+        );
+
         self.register_binding(
             KeyCombination {
                 key_kind: KeyEventKind::Press,
@@ -217,3 +974,185 @@ impl Keybindings {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctrl_a() -> KeyCombination {
+        KeyCombination {
+            key_kind: KeyEventKind::Press,
+            modifier: KeyModifiers::CONTROL,
+            key_code: KeyCode::Char('a'),
+        }
+    }
+
+    #[test]
+    fn ctrl_a_moves_to_the_line_start() {
+        let mut keybindings = Keybindings::default();
+        keybindings.register_common_navigation_bindings();
+        assert_eq!(
+            keybindings.find_binding(ctrl_a()),
+            Some(LineEditorEvent::Movement(vec![
+                MovementCommand::MoveToLineStart
+            ]))
+        );
+        assert!(keybindings.conflicts().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    fn sorted_sequences(keybindings: &Keybindings) -> Vec<(String, LineEditorEvent)> {
+        let mut sequences: Vec<_> = keybindings
+            .sequence_bindings()
+            .into_iter()
+            .map(|(sequence, event)| (format!("{:?}", sequence), event))
+            .collect();
+        sequences.sort_by(|a, b| a.0.cmp(&b.0));
+        sequences
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn toml_round_trip_of_the_emacs_preset() {
+        let mut keybindings = Keybindings::emacs();
+        keybindings.set_sequence_timeout(Duration::from_millis(250));
+        keybindings.register_sequence_binding(
+            vec![ctrl_a(), "ctrl+shift+f5".parse().unwrap()],
+            LineEditorEvent::Edit(vec![EditCommand::InsertString("a \"quoted\" text".into())]),
+        );
+
+        let config = keybindings.to_toml().unwrap();
+        assert!(config.starts_with("sequence_timeout_ms = 250\n"));
+        let loaded = Keybindings::from_toml(&config).unwrap();
+        assert_eq!(loaded.keybindings(), keybindings.keybindings());
+        assert_eq!(sorted_sequences(&loaded), sorted_sequences(&keybindings));
+        assert_eq!(loaded.sequence_timeout(), Duration::from_millis(250));
+        assert!(loaded.conflicts().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn toml_without_timeout_uses_the_default() {
+        let config = "[bindings]\n\"ctrl+l\" = \"ClearScreen\"\n";
+        let keybindings = Keybindings::from_toml(config).unwrap();
+        assert_eq!(keybindings.sequence_timeout(), Duration::from_millis(1000));
+        assert_eq!(keybindings.keybindings().len(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn toml_errors_describe_the_invalid_entry() {
+        let error = |config: &str| {
+            let error = Keybindings::from_toml(config).err().unwrap();
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+            error.to_string()
+        };
+
+        let message = error("[bindings]\n\"ctrl+nope\" = \"ClearScreen\"\n");
+        assert!(message.contains("Invalid key combination `ctrl+nope`, unknown key `nope`"));
+
+        let message = error("[bindings]\n\"hyperctrl+a\" = \"ClearScreen\"\n");
+        assert!(message.contains("unknown modifier `hyperctrl`"));
+
+        let message = error("[sequences]\n\" \" = \"ClearScreen\"\n");
+        assert!(message.contains("Invalid key sequence, missing key"));
+
+        let message = error("[bindings]\n\"ctrl+l\" = \"ClearTheScreen\"\n");
+        assert!(message.contains("ClearTheScreen"));
+
+        let message = error("sequence_timeout_ms = \"fast\"\n");
+        assert!(message.contains("sequence_timeout_ms"));
+    }
+
+    fn ctrl(ch: char) -> KeyCombination {
+        KeyCombination {
+            key_kind: KeyEventKind::Press,
+            modifier: KeyModifiers::CONTROL,
+            key_code: KeyCode::Char(ch),
+        }
+    }
+
+    #[test]
+    fn merge_user_bindings_over_a_preset() {
+        let mut user = Keybindings::default();
+        user.register_binding(ctrl_a(), LineEditorEvent::SelectAll);
+        user.register_binding(
+            ctrl('t'),
+            LineEditorEvent::Edit(vec![EditCommand::TransposeChars]),
+        );
+        user.register_binding(ctrl('g'), LineEditorEvent::ClearScreen);
+
+        let mut keybindings = Keybindings::emacs();
+        let preset_len = keybindings.keybindings().len();
+        keybindings.merge(user);
+
+        assert_eq!(keybindings.keybindings().len(), preset_len + 1);
+        assert_eq!(
+            keybindings.find_binding(ctrl_a()),
+            Some(LineEditorEvent::SelectAll)
+        );
+        assert_eq!(
+            keybindings.find_binding(ctrl('g')),
+            Some(LineEditorEvent::ClearScreen)
+        );
+
+        // Only the replaced event is a conflict, the same event bound again is not
+        let conflicts = keybindings.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].keys, vec![ctrl_a()]);
+        assert_eq!(
+            conflicts[0].previous,
+            LineEditorEvent::Movement(vec![MovementCommand::MoveToLineStart])
+        );
+        assert_eq!(conflicts[0].event, LineEditorEvent::SelectAll);
+
+        keybindings.clear_conflicts();
+        assert!(keybindings.conflicts().is_empty());
+    }
+
+    #[test]
+    fn merge_sequences_and_their_conflicts() {
+        let edit_sequence = vec![ctrl('x'), ctrl('e')];
+        let mut user = Keybindings::default();
+        user.register_sequence_binding(edit_sequence.clone(), LineEditorEvent::ClearScreen);
+        user.register_sequence_binding(vec![ctrl('x'), ctrl('s')], LineEditorEvent::Enter);
+
+        let mut keybindings = Keybindings::emacs();
+        keybindings.merge(user);
+
+        let sequences = keybindings.sequence_bindings();
+        assert!(sequences.contains(&(edit_sequence.clone(), LineEditorEvent::ClearScreen)));
+        assert!(sequences.contains(&(vec![ctrl('x'), ctrl('s')], LineEditorEvent::Enter)));
+        assert!(matches!(
+            keybindings.find_sequence_binding(&[ctrl('x')]),
+            KeySequenceMatch::Prefix
+        ));
+
+        let conflicts = keybindings.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].keys, edit_sequence);
+        assert_eq!(conflicts[0].previous, LineEditorEvent::EditInExternalEditor);
+        assert_eq!(conflicts[0].event, LineEditorEvent::ClearScreen);
+    }
+
+    #[test]
+    fn selection_bindings_replace_ctrl_a_with_select_all() {
+        let mut keybindings = Keybindings::default();
+        keybindings.register_common_navigation_bindings();
+        keybindings.register_common_selection_bindings();
+        assert_eq!(
+            keybindings.find_binding(ctrl_a()),
+            Some(LineEditorEvent::SelectAll)
+        );
+        assert_eq!(keybindings.conflicts().len(), 1);
+        assert_eq!(keybindings.conflicts()[0].keys, vec![ctrl_a()]);
+
+        let emacs = Keybindings::emacs();
+        assert_eq!(
+            emacs.find_binding(ctrl_a()),
+            Some(LineEditorEvent::Movement(vec![
+                MovementCommand::MoveToLineStart
+            ]))
+        );
+    }
+}
@@ -1,6 +1,9 @@
+pub mod edit_mode;
 pub mod editor;
 pub mod event;
+pub mod event_source;
 pub mod input_filter;
 pub mod keybindings;
 pub mod style;
 pub mod styled_buffer;
+pub mod word_splitter;
@@ -2,7 +2,7 @@ use crossterm::style::Attribute;
 use crossterm::style::Color;
 
 /// Represent the foreground, background colors and attributes
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct Style {
     /// Optional foreground color
     foreground: Option<Color>,
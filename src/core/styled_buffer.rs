@@ -1,4 +1,9 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
 use super::style::Style;
+use super::word_splitter::WordSplitter;
+use crate::completion::Span;
 
 /// Memory representation of the lines and styles
 pub struct StyledBuffer {
@@ -35,14 +40,14 @@ impl StyledBuffer {
     pub fn insert_char(&mut self, ch: char) {
         self.buffer.insert(self.cursor_position, ch);
         self.styles.insert(self.cursor_position, Style::default());
-        self.move_char_right();
+        self.cursor_position += 1;
     }
 
     /// Insert character at the current position with style
     pub fn insert_styled_char(&mut self, ch: char, style: Style) {
         self.buffer.insert(self.cursor_position, ch);
         self.styles.insert(self.cursor_position, style);
-        self.move_char_right();
+        self.cursor_position += 1;
     }
 
     /// Insert string at the current position with default style
@@ -59,57 +64,135 @@ impl StyledBuffer {
         }
     }
 
-    /// Safe Move the cursor position to the right
+    /// Safe Move the cursor position to the right by one grapheme cluster
     pub fn move_char_right(&mut self) {
-        if self.cursor_position < self.len() {
-            self.cursor_position += 1;
-        }
+        self.cursor_position = self.next_grapheme_boundary(self.cursor_position);
     }
 
-    /// Safe Move the cursor position to the left
+    /// Safe Move the cursor position to the left by one grapheme cluster
     pub fn move_char_left(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
+        self.cursor_position = self.previous_grapheme_boundary(self.cursor_position);
+    }
+
+    /// Return the start of the grapheme cluster after the position, or the end of the buffer
+    ///
+    /// A grapheme cluster is one visible character that can be made of many chars,
+    /// like a letter with combining accents, a flag or an emoji ZWJ sequence
+    pub fn next_grapheme_boundary(&self, position: usize) -> usize {
+        self.grapheme_boundaries()
+            .into_iter()
+            .find(|boundary| *boundary > position)
+            .unwrap_or(self.len())
+    }
+
+    /// Return the start of the grapheme cluster before the position, or the start of the buffer
+    pub fn previous_grapheme_boundary(&self, position: usize) -> usize {
+        self.grapheme_boundaries()
+            .into_iter()
+            .rev()
+            .find(|boundary| *boundary < position)
+            .unwrap_or(0)
+    }
+
+    /// Positions where every grapheme cluster starts, followed by the end of the buffer
+    pub fn grapheme_boundaries(&self) -> Vec<usize> {
+        let literal = self.literal();
+        let mut boundaries = Vec::with_capacity(self.len() + 1);
+        let mut position = 0;
+        for grapheme in literal.graphemes(true) {
+            boundaries.push(position);
+            position += grapheme.chars().count();
         }
+        boundaries.push(position);
+        boundaries
     }
 
     /// Move the cursor to the begin of the next right word
     pub fn move_word_right(&mut self) {
-        while self.cursor_position < self.len() {
-            if self.buffer[self.cursor_position].is_whitespace() {
-                if self.cursor_position != self.len() {
-                    self.cursor_position += 1;
-                }
-                return;
-            }
-            self.cursor_position += 1;
-        }
+        self.cursor_position = self.next_word_start(&WordSplitter::Whitespace);
     }
 
-    /// Move the cursor to the begin of the next right word
+    /// Move the cursor to the begin of the previous left word
     pub fn move_word_left(&mut self) {
-        self.cursor_position = usize::min(self.cursor_position, self.len() - 1);
-        if self.cursor_position != 0 {
-            self.cursor_position -= 1;
-        }
-
-        while 0 != self.cursor_position {
-            if self.buffer[self.cursor_position].is_whitespace() {
-                self.cursor_position -= 1;
-                continue;
-            }
-            break;
-        }
-
-        while 0 != self.cursor_position {
-            if self.buffer[self.cursor_position].is_whitespace() {
-                if self.cursor_position + 1 < self.len() {
-                    self.cursor_position += 1;
-                }
-                return;
-            }
-            self.cursor_position -= 1;
-        }
+        self.cursor_position = self.previous_word_start(&WordSplitter::Whitespace);
+    }
+
+    /// Return the start of the first word after the cursor, or the end of the buffer
+    pub fn next_word_start(&self, splitter: &WordSplitter) -> usize {
+        self.next_word_start_at(self.cursor_position, splitter)
+    }
+
+    /// Return the start of the first word after the position, or the end of the buffer
+    pub fn next_word_start_at(&self, position: usize, splitter: &WordSplitter) -> usize {
+        splitter
+            .split(&self.buffer)
+            .iter()
+            .map(|span| span.start)
+            .find(|start| *start > position)
+            .unwrap_or(self.len())
+    }
+
+    /// Return the end of the word under or after the cursor, or the end of the buffer
+    pub fn next_word_end(&self, splitter: &WordSplitter) -> usize {
+        splitter
+            .split(&self.buffer)
+            .iter()
+            .map(|span| span.end)
+            .find(|end| *end > self.cursor_position)
+            .unwrap_or(self.len())
+    }
+
+    /// Return the start of the word before the cursor, or the start of the buffer
+    pub fn previous_word_start(&self, splitter: &WordSplitter) -> usize {
+        self.previous_word_start_at(self.cursor_position, splitter)
+    }
+
+    /// Return the start of the word before the position, or the start of the buffer
+    pub fn previous_word_start_at(&self, position: usize, splitter: &WordSplitter) -> usize {
+        splitter
+            .split(&self.buffer)
+            .iter()
+            .rev()
+            .map(|span| span.start)
+            .find(|start| *start < position)
+            .unwrap_or(0)
+    }
+
+    /// Return the span of the word that contains or ends at the cursor,
+    /// or an empty span at the cursor if there is no such word
+    pub fn current_word_span(&self, splitter: &WordSplitter) -> Span {
+        splitter
+            .split(&self.buffer)
+            .into_iter()
+            .find(|span| span.start < self.cursor_position && self.cursor_position <= span.end)
+            .unwrap_or(Span::new(self.cursor_position, self.cursor_position))
+    }
+
+    /// Return the span of the word under the position, or the word that ends at it,
+    /// or an empty span at the position if there is no such word
+    pub fn word_span_at(&self, position: usize, splitter: &WordSplitter) -> Span {
+        let spans = splitter.split(&self.buffer);
+        spans
+            .iter()
+            .find(|span| span.start <= position && position < span.end)
+            .or_else(|| spans.iter().find(|span| span.end == position))
+            .map_or(Span::new(position, position), |span| {
+                Span::new(span.start, span.end)
+            })
+    }
+
+    /// Return the span of the line that contains the position, without the new lines around it
+    pub fn line_span_at(&self, position: usize) -> Span {
+        let position = usize::min(position, self.buffer.len());
+        let start = self.buffer[..position]
+            .iter()
+            .rposition(|ch| *ch == '\n')
+            .map_or(0, |i| i + 1);
+        let end = self.buffer[position..]
+            .iter()
+            .position(|ch| *ch == '\n')
+            .map_or(self.buffer.len(), |i| position + i);
+        Span::new(start, end)
     }
 
     /// Move cursor to the start of the buffer
@@ -122,26 +205,72 @@ impl StyledBuffer {
         self.cursor_position = self.buffer.len();
     }
 
-    /// Deletes one character to the right
+    /// Get the position after the new line before the cursor, or the start of the buffer
+    pub fn line_start(&self) -> usize {
+        self.buffer[..self.cursor_position]
+            .iter()
+            .rposition(|ch| *ch == '\n')
+            .map_or(0, |i| i + 1)
+    }
+
+    /// Get the position of the new line after the cursor, or the end of the buffer
+    pub fn line_end(&self) -> usize {
+        self.buffer[self.cursor_position..]
+            .iter()
+            .position(|ch| *ch == '\n')
+            .map_or(self.buffer.len(), |i| self.cursor_position + i)
+    }
+
+    /// Move cursor after the new line before it, or to the start of the buffer
+    pub fn move_to_line_start(&mut self) {
+        self.cursor_position = self.line_start();
+    }
+
+    /// Move cursor to the new line after it, or to the end of the buffer
+    pub fn move_to_line_end(&mut self) {
+        self.cursor_position = self.line_end();
+    }
+
+    /// Deletes the grapheme cluster under the cursor, no-op at the end of the buffer
     pub fn delete_right_char(&mut self) {
-        if self.cursor_position + 1 < self.buffer.len() {
-            self.buffer.remove(self.cursor_position + 1);
-            self.styles.remove(self.cursor_position + 1);
-        }
+        let end = self.next_grapheme_boundary(self.cursor_position);
+        let position = self.cursor_position;
+        self.delete_range(position, end);
     }
 
-    /// Deletes one character to the left
+    /// Deletes one grapheme cluster to the left
     pub fn delete_left_char(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
-            self.buffer.remove(self.cursor_position);
-            self.styles.remove(self.cursor_position);
+        let start = self.previous_grapheme_boundary(self.cursor_position);
+        let position = self.cursor_position;
+        self.delete_range(start, position);
+    }
+
+    /// Swap the grapheme clusters around the cursor and move the cursor after them,
+    /// at the end of the buffer the last two grapheme clusters are swapped
+    pub fn transpose_chars(&mut self) {
+        if self.cursor_position == 0 {
+            return;
+        }
+
+        let mut position = self.cursor_position;
+        if position == self.len() {
+            position = self.previous_grapheme_boundary(position);
         }
+
+        let start = self.previous_grapheme_boundary(position);
+        let end = self.next_grapheme_boundary(position);
+        if start == position || position == end {
+            return;
+        }
+
+        self.buffer[start..end].rotate_left(position - start);
+        self.styles[start..end].rotate_left(position - start);
+        self.cursor_position = end;
     }
 
-    /// Deletes range of characters and styles from buffer
+    /// Deletes the half-open range `from..to` of characters and styles from buffer
     pub fn delete_range(&mut self, from: usize, to: usize) {
-        if to <= self.len() {
+        if from <= to && to <= self.len() {
             self.buffer.drain(from..to);
             self.styles.drain(from..to);
             self.cursor_position = from;
@@ -159,12 +288,12 @@ impl StyledBuffer {
         literal.to_string()
     }
 
-    /// Get char at position
+    /// Get char at position, or None if the position is at or after the end of the buffer
     pub fn char_at(&self, position: usize) -> Option<char> {
-        Some(self.buffer[position])
+        self.buffer.get(position).copied()
     }
 
-    /// Get the sub string from the provided range, or None if range is invalid
+    /// Get the sub string from the half-open range `start..end`, or None if range is invalid or empty
     pub fn sub_string(&self, start: usize, end: usize) -> Option<String> {
         if start < end && end <= self.len() {
             let slice: String = self.buffer[start..end].iter().clone().collect();
@@ -211,7 +340,7 @@ impl StyledBuffer {
         self.styles[position] = style;
     }
 
-    /// Set style for a range of characters
+    /// Set style for the half-open range `start..end` of characters
     pub fn style_range(&mut self, start: usize, end: usize, style: Style) {
         let max = std::cmp::min(end, self.styles.len());
         for i in start..max {
@@ -260,8 +389,101 @@ impl StyledBuffer {
         self.buffer.len()
     }
 
+    /// Number of terminal columns needed to display the buffer,
+    /// wide characters take two columns and control characters take none
+    pub fn width(&self) -> usize {
+        self.buffer.iter().map(|ch| ch.width().unwrap_or(0)).sum()
+    }
+
+    /// Number of terminal columns needed to display the buffer as a prompt,
+    /// like [`StyledBuffer::width`] but the ANSI escape sequences embedded in the prompt
+    /// text, like SGR colors, are not counted
+    pub fn prompt_width(&self) -> usize {
+        let mut width = 0;
+        let mut chars = self.buffer.iter().peekable();
+        while let Some(ch) = chars.next() {
+            if *ch != '\x1b' {
+                width += ch.width().unwrap_or(0);
+                continue;
+            }
+
+            match chars.next() {
+                // CSI sequence ends with a character in the range `@` to `~`
+                Some('[') => {
+                    for ch in chars.by_ref() {
+                        if ('@'..='~').contains(ch) {
+                            break;
+                        }
+                    }
+                }
+                // OSC sequence ends with BEL or the `ESC \` string terminator
+                Some(']') => {
+                    while let Some(ch) = chars.next() {
+                        if *ch == '\x07' {
+                            break;
+                        }
+                        if *ch == '\x1b' && chars.peek() == Some(&&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        width
+    }
+
     /// Returns `true` if the buffer contains no elements.
     pub fn is_empty(&self) -> bool {
         self.buffer.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_spans_of_multibyte_characters() {
+        let mut buffer = StyledBuffer::from("çà ünïcödé");
+        let splitter = WordSplitter::Whitespace;
+        let span = buffer.current_word_span(&splitter);
+        assert_eq!((span.start, span.end), (3, 10));
+
+        let span = buffer.word_span_at(1, &splitter);
+        assert_eq!((span.start, span.end), (0, 2));
+        assert_eq!(buffer.sub_string(span.start, span.end).unwrap(), "çà");
+
+        buffer.delete_range(3, 10);
+        assert_eq!(buffer.literal(), "çà ");
+    }
+
+    #[test]
+    fn width_counts_every_printable_character() {
+        assert_eq!(StyledBuffer::from("a日b").width(), 4);
+        assert_eq!(StyledBuffer::from("\x1b[1m").width(), 3);
+        assert_eq!(StyledBuffer::from("\x1b]0;title\x07").width(), 8);
+    }
+
+    #[test]
+    fn prompt_width_skips_ansi_escape_sequences() {
+        assert_eq!(StyledBuffer::from("\x1b[1;32m❯\x1b[0m ").prompt_width(), 2);
+        assert_eq!(StyledBuffer::from("\x1b]0;title\x07> ").prompt_width(), 2);
+        assert_eq!(
+            StyledBuffer::from("\x1b]0;title\x1b\\日> ").prompt_width(),
+            4
+        );
+        assert_eq!(StyledBuffer::from("a\x1bb").prompt_width(), 1);
+    }
+
+    #[test]
+    fn grapheme_boundaries_of_clusters() {
+        let buffer = StyledBuffer::from("e\u{301}\u{1F1EB}\u{1F1F7}\u{1F468}\u{200D}\u{1F469}x");
+        assert_eq!(buffer.grapheme_boundaries(), vec![0, 2, 4, 7, 8]);
+        assert_eq!(buffer.next_grapheme_boundary(0), 2);
+        assert_eq!(buffer.next_grapheme_boundary(4), 7);
+        assert_eq!(buffer.previous_grapheme_boundary(7), 4);
+        assert_eq!(buffer.previous_grapheme_boundary(2), 0);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::completion::Span;
+
+/// Defines how the buffer is split into words for the word commands and completion spans
+#[derive(Clone, Default)]
+pub enum WordSplitter {
+    /// Words are separated by whitespace
+    #[default]
+    Whitespace,
+    /// Words are separated by whitespace, but text between single or double quotes is one word
+    /// even if it contains whitespace, and a quote escaped with backslash does not open or close it
+    QuoteAware,
+    /// Words are separated by whitespace and any of the separator characters,
+    /// for example `/` and `.` to move between path components
+    Separators(Vec<char>),
+}
+
+impl WordSplitter {
+    /// Split the characters into the list of word spans
+    pub fn split(&self, chars: &[char]) -> Vec<Span> {
+        let mut spans = vec![];
+        let mut position = 0;
+
+        while position < chars.len() {
+            if self.is_separator(chars[position]) {
+                position += 1;
+                continue;
+            }
+
+            let start = position;
+            let mut quote: Option<char> = None;
+            let mut is_escaped = false;
+
+            while position < chars.len() {
+                let ch = chars[position];
+                if quote.is_none() && self.is_separator(ch) {
+                    break;
+                }
+
+                if let WordSplitter::QuoteAware = self {
+                    if is_escaped {
+                        is_escaped = false;
+                    } else if ch == '\\' {
+                        is_escaped = true;
+                    } else if quote == Some(ch) {
+                        quote = None;
+                    } else if quote.is_none() && (ch == '"' || ch == '\'') {
+                        quote = Some(ch);
+                    }
+                }
+
+                position += 1;
+            }
+
+            spans.push(Span::new(start, position));
+        }
+
+        spans
+    }
+
+    /// Returns true if the character separates two words
+    fn is_separator(&self, ch: char) -> bool {
+        match self {
+            WordSplitter::Separators(separators) => ch.is_whitespace() || separators.contains(&ch),
+            _ => ch.is_whitespace(),
+        }
+    }
+}
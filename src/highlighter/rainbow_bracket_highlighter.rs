@@ -0,0 +1,101 @@
+use crossterm::style::Color;
+
+use crate::style::Style;
+use crate::styled_buffer::StyledBuffer;
+
+use super::Highlighter;
+
+/// Highlighter that colors the nested `()`, `[]` and `{}` brackets by their depth,
+/// cycling through a palette of styles
+///
+/// Brackets inside single or double quoted strings and unmatched closing brackets are not styled
+pub struct RainbowBracketHighlighter {
+    palette: Vec<Style>,
+}
+
+impl Default for RainbowBracketHighlighter {
+    fn default() -> Self {
+        let palette = [Color::Yellow, Color::Magenta, Color::Cyan, Color::Green]
+            .into_iter()
+            .map(|color| {
+                let mut style = Style::default();
+                style.set_foreground_color(color);
+                style
+            })
+            .collect();
+        RainbowBracketHighlighter { palette }
+    }
+}
+
+impl RainbowBracketHighlighter {
+    /// Create instance of RainbowBracketHighlighter with the default palette,
+    /// yellow, magenta, cyan and green
+    pub fn new() -> Self {
+        RainbowBracketHighlighter::default()
+    }
+
+    /// Set the styles used for each depth, the first one for the outermost brackets,
+    /// an empty palette disables the highlighter
+    pub fn with_palette(mut self, palette: Vec<Style>) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Style of the brackets at the depth, cycling through the palette
+    fn depth_style(&self, depth: usize) -> Option<Style> {
+        if self.palette.is_empty() {
+            return None;
+        }
+        Some(self.palette[depth % self.palette.len()].clone())
+    }
+}
+
+impl Highlighter for RainbowBracketHighlighter {
+    fn highlight(&self, buffer: &mut StyledBuffer) {
+        let mut depth = 0;
+        let mut quote: Option<char> = None;
+        let mut is_escaped = false;
+
+        for position in 0..buffer.len() {
+            let Some(ch) = buffer.char_at(position) else {
+                continue;
+            };
+
+            if let Some(quote_char) = quote {
+                if is_escaped {
+                    is_escaped = false;
+                } else if ch == '\\' {
+                    is_escaped = true;
+                } else if ch == quote_char {
+                    quote = None;
+                }
+                continue;
+            }
+
+            match ch {
+                '"' | '\'' => quote = Some(ch),
+                '(' | '[' | '{' => {
+                    if let Some(style) = self.depth_style(depth) {
+                        buffer.style_char(position, style);
+                    }
+                    depth += 1;
+                }
+                ')' | ']' | '}' if depth > 0 => {
+                    depth -= 1;
+                    if let Some(style) = self.depth_style(depth) {
+                        buffer.style_char(position, style);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
+    fn is_enabled(&self) -> bool {
+        !self.palette.is_empty()
+    }
+}
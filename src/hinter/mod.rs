@@ -5,4 +5,43 @@ use crate::styled_buffer::StyledBuffer;
 pub trait Hinter {
     /// The action that will handle the current styled buffer as a line
     fn hint(&self, buffer: &mut StyledBuffer) -> Option<StyledBuffer>;
+
+    /// Returns false to skip this hinter without removing it from the line editor
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// The name used to remove this hinter from the line editor, None if it has no name
+    fn name(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Hinter registered with a name, delegates everything else to the inner hinter
+pub(crate) struct NamedHinter {
+    name: String,
+    inner: Box<dyn Hinter>,
+}
+
+impl NamedHinter {
+    pub(crate) fn new(name: &str, inner: Box<dyn Hinter>) -> Self {
+        NamedHinter {
+            name: name.to_string(),
+            inner,
+        }
+    }
+}
+
+impl Hinter for NamedHinter {
+    fn hint(&self, buffer: &mut StyledBuffer) -> Option<StyledBuffer> {
+        self.inner.hint(buffer)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.inner.is_enabled()
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
 }
@@ -0,0 +1,262 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::io::Write;
+use std::path::PathBuf;
+
+use super::History;
+
+/// History that is loaded from a file and appends every new entry to it,
+/// one entry per line so it is kept across sessions, a new line or a backslash
+/// inside an entry is escaped as `\\n` or `\\\\` so multiline entries stay whole
+pub struct FileHistory {
+    path: PathBuf,
+    entries: Vec<String>,
+    capacity: Option<usize>,
+    /// Number of entries in the file, it can be above the capacity by the slack
+    file_entries: usize,
+}
+
+impl FileHistory {
+    /// Create instance of FileHistory with the entries stored in the file,
+    /// the file is created on the first append if it does not exist
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        FileHistory::load(path.into(), None)
+    }
+
+    /// Create instance of FileHistory that keeps only the newest `max_entries` entries,
+    /// the file is rewritten with them only once it grows past the capacity by a slack
+    /// of a quarter of the capacity, so not every append rewrites the whole file
+    pub fn with_capacity(path: impl Into<PathBuf>, max_entries: usize) -> Result<Self> {
+        FileHistory::load(path.into(), Some(max_entries))
+    }
+
+    /// Read the entries from the file, skipping empty lines and lines that are not valid UTF-8
+    fn load(path: PathBuf, capacity: Option<usize>) -> Result<Self> {
+        let content = match fs::read(&path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => vec![],
+            Err(error) => return Err(error),
+        };
+
+        let entries: Vec<String> = content
+            .split(|byte| *byte == b'\n')
+            .filter_map(|line| String::from_utf8(line.to_vec()).ok())
+            .filter(|line| !line.is_empty())
+            .map(|line| unescape(&line))
+            .collect();
+
+        let mut history = FileHistory {
+            path,
+            file_entries: entries.len(),
+            entries,
+            capacity,
+        };
+
+        history.truncate_to_capacity();
+        if history.is_file_over_slack() {
+            history.rewrite()?;
+        }
+
+        Ok(history)
+    }
+
+    /// Remove the oldest entries above the capacity, returns true if any entry is removed
+    fn truncate_to_capacity(&mut self) -> bool {
+        match self.capacity {
+            Some(capacity) if self.entries.len() > capacity => {
+                let overflow = self.entries.len() - capacity;
+                self.entries.drain(..overflow);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the file has more entries than the capacity plus the slack
+    fn is_file_over_slack(&self) -> bool {
+        match self.capacity {
+            Some(capacity) => self.file_entries > capacity + capacity / 4,
+            None => false,
+        }
+    }
+
+    /// Write all the entries to a temporary file next to the history file and rename it
+    /// over the history file, so the entries are not lost if the write is interrupted
+    fn rewrite(&mut self) -> Result<()> {
+        let mut temp_name = self.path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = self.path.with_file_name(temp_name);
+
+        let mut content = String::new();
+        for entry in &self.entries {
+            content.push_str(&escape(entry));
+            content.push('\n');
+        }
+
+        if let Err(error) = fs::write(&temp_path, content) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(error);
+        }
+        fs::rename(&temp_path, &self.path)?;
+        self.file_entries = self.entries.len();
+        Ok(())
+    }
+
+    /// Append one entry to the end of the file
+    fn write_entry(&self, entry: &str) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", escape(entry))?;
+        file.flush()
+    }
+}
+
+/// Escape the new lines and backslashes of an entry so it is stored in one line
+fn escape(entry: &str) -> String {
+    entry.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Restore the new lines and backslashes of an entry escaped by [`escape`],
+/// a backslash that is not part of an escape is kept as it is
+fn unescape(line: &str) -> String {
+    let mut entry = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            entry.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => entry.push('\n'),
+            Some('\\') => entry.push('\\'),
+            Some(other) => {
+                entry.push('\\');
+                entry.push(other);
+            }
+            None => entry.push('\\'),
+        }
+    }
+    entry
+}
+
+impl History for FileHistory {
+    /// Append the entry and write it to the file, if the file can't be written
+    /// the entry is still available for the current session
+    fn append(&mut self, entry: &str) {
+        self.entries.push(entry.to_string());
+        self.truncate_to_capacity();
+        if self.write_entry(entry).is_ok() {
+            self.file_entries += 1;
+        }
+        if self.is_file_over_slack() {
+            let _ = self.rewrite();
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<String> {
+        self.entries.get(index).cloned()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Path in the temporary directory that is removed with its temporary file when dropped
+    struct TestPath(PathBuf);
+
+    impl TestPath {
+        fn new(name: &str) -> Self {
+            let file_name = format!("lineeditor-history-{}-{}", std::process::id(), name);
+            let path = TestPath(std::env::temp_dir().join(file_name));
+            let _ = fs::remove_file(&path.0);
+            path
+        }
+
+        fn lines(&self) -> Vec<String> {
+            fs::read_to_string(&self.0)
+                .unwrap()
+                .lines()
+                .map(String::from)
+                .collect()
+        }
+    }
+
+    impl Drop for TestPath {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn entries(history: &FileHistory) -> Vec<String> {
+        (0..history.len()).filter_map(|i| history.get(i)).collect()
+    }
+
+    #[test]
+    fn load_skips_empty_lines_and_missing_file() {
+        let path = TestPath::new("load");
+        assert!(FileHistory::new(&path.0).unwrap().is_empty());
+
+        fs::write(&path.0, "ls\n\ncd /tmp\n").unwrap();
+        let history = FileHistory::new(&path.0).unwrap();
+        assert_eq!(entries(&history), vec!["ls", "cd /tmp"]);
+    }
+
+    #[test]
+    fn load_skips_invalid_utf8_lines() {
+        let path = TestPath::new("utf8");
+        fs::write(&path.0, b"ls\n\xff\xfe\npwd\n").unwrap();
+        let history = FileHistory::new(&path.0).unwrap();
+        assert_eq!(entries(&history), vec!["ls", "pwd"]);
+    }
+
+    #[test]
+    fn entries_round_trip_through_the_file() {
+        let path = TestPath::new("round-trip");
+        let written = [
+            "echo 'a\nb'",
+            "C:\\dir\\new",
+            "tab\\n literal",
+            "çà ünïcödé",
+        ];
+
+        let mut history = FileHistory::new(&path.0).unwrap();
+        for entry in written {
+            history.append(entry);
+        }
+        assert_eq!(path.lines().len(), written.len());
+
+        let history = FileHistory::new(&path.0).unwrap();
+        assert_eq!(entries(&history), written);
+    }
+
+    #[test]
+    fn capacity_rewrites_the_file_after_the_slack() {
+        let path = TestPath::new("capacity");
+        let mut history = FileHistory::with_capacity(&path.0, 4).unwrap();
+        for i in 0..5 {
+            history.append(&i.to_string());
+        }
+
+        // One entry over the capacity is within the slack so it is only appended
+        assert_eq!(entries(&history), vec!["1", "2", "3", "4"]);
+        assert_eq!(path.lines(), vec!["0", "1", "2", "3", "4"]);
+
+        history.append("5");
+        assert_eq!(entries(&history), vec!["2", "3", "4", "5"]);
+        assert_eq!(path.lines(), vec!["2", "3", "4", "5"]);
+
+        let reloaded = FileHistory::with_capacity(&path.0, 2).unwrap();
+        assert_eq!(entries(&reloaded), vec!["4", "5"]);
+        assert_eq!(path.lines(), vec!["4", "5"]);
+        assert!(!std::path::Path::new(&format!("{}.tmp", path.0.display())).exists());
+    }
+}
@@ -0,0 +1,65 @@
+pub mod file_history;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The History trait, Implementers of this trait store the lines submitted by the user
+/// ordered from the oldest to the newest entry
+pub trait History {
+    /// Append a new entry as the newest one
+    fn append(&mut self, entry: &str);
+
+    /// Get the entry at index where 0 is the oldest entry, or None if index is out of range
+    fn get(&self, index: usize) -> Option<String>;
+
+    /// Number of stored entries
+    fn len(&self) -> usize;
+
+    /// Returns true if there are no stored entries
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// In memory History that lives as long as the line editor
+#[derive(Default)]
+pub struct VecHistory {
+    entries: Vec<String>,
+}
+
+impl VecHistory {
+    /// Create instance of VecHistory with initial entries ordered from the oldest
+    pub fn with_entries(entries: Vec<String>) -> Self {
+        VecHistory { entries }
+    }
+}
+
+impl History for VecHistory {
+    fn append(&mut self, entry: &str) {
+        self.entries.push(entry.to_string());
+    }
+
+    fn get(&self, index: usize) -> Option<String> {
+        self.entries.get(index).cloned()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Shared History, so the same entries can be used by the line editor and for example
+/// a [`crate::HistoryCompleter`]
+impl<H: History + ?Sized> History for Rc<RefCell<H>> {
+    fn append(&mut self, entry: &str) {
+        self.borrow_mut().append(entry);
+    }
+
+    fn get(&self, index: usize) -> Option<String> {
+        self.borrow().get(index)
+    }
+
+    fn len(&self) -> usize {
+        self.borrow().len()
+    }
+}
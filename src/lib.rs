@@ -1,14 +1,23 @@
 mod core;
+pub use core::edit_mode;
 pub use core::editor;
 pub use core::event;
+pub use core::event_source;
 pub use core::input_filter;
 pub use core::keybindings;
 pub use core::style;
 pub use core::styled_buffer;
+pub use core::word_splitter;
 
 mod engine;
+pub use engine::BellStyle;
+pub use engine::DeleteAtEnd;
+pub use engine::EditorState;
 pub use engine::LineEditor;
+pub use engine::LineEditorBuilder;
 pub use engine::LineEditorResult;
+pub use engine::MaskStrength;
+pub use engine::StrengthColor;
 
 mod prompt;
 pub use prompt::Prompt;
@@ -19,22 +28,46 @@ pub use autopair::AutoPair;
 pub use autopair::DefaultAutoPair;
 pub use autopair::DEFAULT_PAIRS;
 
+mod clipboard;
+pub use clipboard::Clipboard;
+pub use clipboard::MemoryClipboard;
+pub use clipboard::Osc52Clipboard;
+pub use clipboard::SystemClipboard;
+
 mod hinter;
 pub use hinter::Hinter;
 
 mod highlighter;
+pub use highlighter::rainbow_bracket_highlighter::RainbowBracketHighlighter;
 pub use highlighter::Highlighter;
 
+mod history;
+pub use history::file_history::FileHistory;
+pub use history::History;
+pub use history::VecHistory;
+
+mod validator;
+pub use validator::ValidationResult;
+pub use validator::Validator;
+
 mod completion;
+pub use completion::chained_completer::ChainedCompleter;
+pub use completion::fuzzy_completer::FuzzyCompleter;
+pub use completion::history_completer::HistoryCompleter;
 pub use completion::Completer;
 pub use completion::Span;
 pub use completion::Suggestion;
 
 mod view;
 pub use view::drop_down_list_view::DropDownListView;
+pub use view::grid_list_view::GridListView;
 pub use view::list_view::ListView;
+pub use view::output::Output;
 pub use view::styled_editor_view;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 // Reexport the key types to be independent from an explicit crossterm dependency.
 pub use crossterm::cursor::SetCursorStyle;
 pub use crossterm::event::KeyCode;
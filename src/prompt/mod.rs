@@ -1,9 +1,32 @@
+use crate::edit_mode::ViMode;
 use crate::styled_buffer::StyledBuffer;
 
 /// The Prompt trait, Implementers of this trait will return a prompt as styled buffer
 pub trait Prompt {
     /// The action that will return prompt with styles as StyledBuffer
     fn prompt(&self) -> StyledBuffer;
+
+    /// The prompt rendered at the start of every line after the first one in multiline input
+    fn continuation_prompt(&self) -> String {
+        "... ".to_string()
+    }
+
+    /// The prompt rendered flush to the right edge of the first line like RPROMPT in zsh,
+    /// it is hidden while the line reaches it, None by default
+    fn right_prompt(&self) -> Option<String> {
+        None
+    }
+
+    /// The minimal prompt that replaces the prompt of a submitted line to keep the scrollback
+    /// clean, or None to keep the prompt, None by default
+    fn transient_prompt(&self) -> Option<String> {
+        None
+    }
+
+    /// The prompt in the vi edit mode, it can show the current vi mode
+    fn prompt_with_mode(&self, _mode: ViMode) -> StyledBuffer {
+        self.prompt()
+    }
 }
 
 pub struct StringPrompt {
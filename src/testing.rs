@@ -0,0 +1,415 @@
+//! Render [`LineEditor::read_line`] sessions to a headless terminal driven by scripted events,
+//! to assert the rendered screen or the written ANSI output in tests without a real terminal
+
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::io::Result;
+use std::io::Write;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use unicode_width::UnicodeWidthChar;
+
+use crate::event_source::EventSource;
+use crate::event_source::ScriptedEventSource;
+use crate::view::base;
+use crate::LineEditor;
+use crate::LineEditorResult;
+
+/// A terminal of a fixed size that records everything the line editor writes to it
+/// and replays it on a grid of cells
+///
+/// A scripted [`Event::Resize`] resizes the terminal when it is read, the output is replayed
+/// on a screen of the last size
+pub struct HeadlessTerminal {
+    size: Rc<Cell<(u16, u16)>>,
+    output: Rc<RefCell<Vec<u8>>>,
+}
+
+impl HeadlessTerminal {
+    /// Create instance of HeadlessTerminal with the number of columns and rows
+    pub fn new(columns: u16, rows: u16) -> Self {
+        HeadlessTerminal {
+            size: Rc::new(Cell::new((columns, rows))),
+            output: Rc::new(RefCell::new(vec![])),
+        }
+    }
+
+    /// Read a line from the events rendering it to this terminal, the output target, the event
+    /// source and the terminal size of the line editor are replaced
+    ///
+    /// Returns an [`std::io::ErrorKind::UnexpectedEof`] error if the events end before the line
+    pub fn read_line(
+        &mut self,
+        line_editor: &mut LineEditor,
+        events: Vec<Event>,
+    ) -> Result<LineEditorResult> {
+        line_editor.set_output(Box::new(SharedOutput(self.output.clone())));
+        line_editor.set_event_source(Box::new(ResizingEventSource {
+            events: ScriptedEventSource::new(events),
+            size: self.size.clone(),
+        }));
+
+        // The line starts where the previous session left the cursor
+        let _guard = HeadlessGuard::new(self.size.get(), self.screen_state().cursor);
+        line_editor.set_terminal_size(self.size.get())?;
+        line_editor.read_line()
+    }
+
+    /// Get everything written to the terminal, including the ANSI escape sequences
+    pub fn ansi(&self) -> String {
+        String::from_utf8_lossy(&self.output.borrow()).into_owned()
+    }
+
+    /// Get the text on the screen without styles, one line per row without the trailing spaces
+    /// and without the empty rows at the end
+    pub fn screen(&self) -> String {
+        self.screen_state().text()
+    }
+
+    /// Get the cursor position (column, row) on the screen
+    pub fn cursor(&self) -> (u16, u16) {
+        self.screen_state().cursor
+    }
+
+    /// Replay the output on an empty screen
+    fn screen_state(&self) -> Screen {
+        let mut screen = Screen::new(self.size.get());
+        screen.write(&String::from_utf8_lossy(&self.output.borrow()));
+        screen
+    }
+}
+
+/// Create the events of a key press with the modifiers
+pub fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent::new(code, modifiers))
+}
+
+/// Create the key press events that type the text, new lines are typed with Enter
+pub fn type_text(text: &str) -> Vec<Event> {
+    text.chars()
+        .map(|ch| match ch {
+            '\n' => key(KeyCode::Enter, KeyModifiers::NONE),
+            ch => key(KeyCode::Char(ch), KeyModifiers::NONE),
+        })
+        .collect()
+}
+
+/// Replay the output on an empty screen of the size (columns, rows) and get its text,
+/// for the views that are rendered without a line editor
+#[cfg(test)]
+pub(crate) fn replay(size: (u16, u16), output: &str) -> String {
+    let mut screen = Screen::new(size);
+    screen.write(output);
+    screen.text()
+}
+
+/// Scripted events that resize the headless terminal when a resize event is read
+struct ResizingEventSource {
+    events: ScriptedEventSource,
+    size: Rc<Cell<(u16, u16)>>,
+}
+
+impl EventSource for ResizingEventSource {
+    fn poll(&mut self, timeout: Duration) -> Result<bool> {
+        self.events.poll(timeout)
+    }
+
+    fn read(&mut self) -> Result<Event> {
+        let event = self.events.read()?;
+        if let Event::Resize(columns, rows) = event {
+            self.size.set((columns, rows));
+            base::set_headless_size(Some((columns, rows)));
+        }
+        Ok(event)
+    }
+}
+
+/// Output target that appends to the bytes shared with the HeadlessTerminal
+pub(crate) struct SharedOutput(pub(crate) Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Render to the headless terminal until it is dropped, even on early return or panic
+struct HeadlessGuard;
+
+impl HeadlessGuard {
+    fn new(size: (u16, u16), cursor: (u16, u16)) -> Self {
+        base::set_headless_size(Some(size));
+        base::record_cursor_position(Some(cursor));
+        HeadlessGuard
+    }
+}
+
+impl Drop for HeadlessGuard {
+    fn drop(&mut self) {
+        base::set_headless_size(None);
+        base::record_cursor_position(None);
+    }
+}
+
+/// Grid of cells that interprets the text and the cursor and clear escape sequences,
+/// the style sequences are ignored
+struct Screen {
+    size: (u16, u16),
+    cells: Vec<Vec<String>>,
+    cursor: (u16, u16),
+    saved_cursor: (u16, u16),
+    /// The last column was printed, the next character starts on the next row
+    pending_wrap: bool,
+}
+
+impl Screen {
+    fn new(size: (u16, u16)) -> Self {
+        Screen {
+            size,
+            cells: vec![vec![" ".to_string(); size.0 as usize]; size.1 as usize],
+            cursor: (0, 0),
+            saved_cursor: (0, 0),
+            pending_wrap: false,
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        let mut chars = text.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\x1b' => match chars.next() {
+                    Some('[') => {
+                        let mut parameters = String::new();
+                        for ch in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&ch) {
+                                self.control_sequence(&parameters, ch);
+                                break;
+                            }
+                            parameters.push(ch);
+                        }
+                    }
+                    Some('7') => self.saved_cursor = self.cursor,
+                    Some('8') => self.move_to(self.saved_cursor.0, self.saved_cursor.1),
+                    _ => {}
+                },
+                '\r' => self.move_to(0, self.cursor.1),
+                '\n' => self.next_row(),
+                '\x07' => {}
+                ch => self.print(ch),
+            }
+        }
+    }
+
+    /// Text of the rows without the trailing spaces and without the empty rows at the end
+    fn text(&self) -> String {
+        let mut rows: Vec<String> = self
+            .cells
+            .iter()
+            .map(|row| row.concat().trim_end().to_string())
+            .collect();
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+        rows.join("\n")
+    }
+
+    fn control_sequence(&mut self, parameters: &str, command: char) {
+        // Private modes like `?2004h` and keyboard flags like `>1u` are not rendered
+        if parameters.starts_with(['?', '>', '<', '=']) {
+            return;
+        }
+
+        let values: Vec<u16> = parameters
+            .split(';')
+            .map(|value| value.trim().parse().unwrap_or(0))
+            .collect();
+        let first = values.first().copied().unwrap_or(0);
+        let count = first.max(1);
+        let (column, row) = self.cursor;
+        match command {
+            'H' | 'f' => {
+                let second = values.get(1).copied().unwrap_or(0);
+                self.move_to(second.max(1) - 1, count - 1);
+            }
+            'G' => self.move_to(count - 1, row),
+            'd' => self.move_to(column, count - 1),
+            'A' => self.move_to(column, row.saturating_sub(count)),
+            'B' => self.move_to(column, row.saturating_add(count)),
+            'C' => self.move_to(column.saturating_add(count), row),
+            'D' => self.move_to(column.saturating_sub(count), row),
+            'E' => self.move_to(0, row.saturating_add(count)),
+            'F' => self.move_to(0, row.saturating_sub(count)),
+            'S' => (0..count).for_each(|_| self.scroll_up()),
+            'J' => match first {
+                0 => {
+                    self.clear_row(row, column as usize);
+                    for row in row + 1..self.size.1 {
+                        self.clear_row(row, 0);
+                    }
+                }
+                _ => (0..self.size.1).for_each(|row| self.clear_row(row, 0)),
+            },
+            'K' => match first {
+                0 => self.clear_row(row, column as usize),
+                _ => self.clear_row(row, 0),
+            },
+            _ => {}
+        }
+    }
+
+    fn print(&mut self, ch: char) {
+        let width = ch.width().unwrap_or(0) as u16;
+        if width == 0 {
+            // Combining characters join the previous cell
+            let column = self.cursor.0.saturating_sub(1) as usize;
+            if let Some(cell) = self.cell(column, self.cursor.1) {
+                cell.push(ch);
+            }
+            return;
+        }
+
+        if self.pending_wrap || self.cursor.0 + width > self.size.0 {
+            self.move_to(0, self.cursor.1);
+            self.next_row();
+        }
+
+        let (column, row) = self.cursor;
+        if let Some(cell) = self.cell(column as usize, row) {
+            *cell = ch.to_string();
+        }
+        for offset in 1..width {
+            if let Some(cell) = self.cell((column + offset) as usize, row) {
+                cell.clear();
+            }
+        }
+
+        if column + width >= self.size.0 {
+            self.cursor.0 = self.size.0.saturating_sub(1);
+            self.pending_wrap = true;
+        } else {
+            self.cursor.0 += width;
+        }
+    }
+
+    fn cell(&mut self, column: usize, row: u16) -> Option<&mut String> {
+        self.cells.get_mut(row as usize)?.get_mut(column)
+    }
+
+    fn move_to(&mut self, column: u16, row: u16) {
+        self.cursor = (
+            column.min(self.size.0.saturating_sub(1)),
+            row.min(self.size.1.saturating_sub(1)),
+        );
+        self.pending_wrap = false;
+    }
+
+    fn next_row(&mut self) {
+        if self.cursor.1 + 1 >= self.size.1 {
+            self.scroll_up();
+        } else {
+            self.cursor.1 += 1;
+        }
+        self.pending_wrap = false;
+    }
+
+    fn scroll_up(&mut self) {
+        if self.cells.is_empty() {
+            return;
+        }
+        self.cells.remove(0);
+        self.cells.push(vec![" ".to_string(); self.size.0 as usize]);
+    }
+
+    fn clear_row(&mut self, row: u16, from: usize) {
+        if let Some(cells) = self.cells.get_mut(row as usize) {
+            for cell in cells.iter_mut().skip(from) {
+                *cell = " ".to_string();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StringPrompt;
+
+    fn line_editor() -> LineEditor {
+        let prompt = StringPrompt::new("> ".to_string());
+        let mut line_editor = LineEditor::new(Box::new(prompt));
+        let bindings = line_editor.keybinding();
+        bindings.register_common_control_bindings();
+        bindings.register_common_navigation_bindings();
+        bindings.register_common_edit_bindings();
+        line_editor
+    }
+
+    #[test]
+    fn read_typed_line() {
+        let mut terminal = HeadlessTerminal::new(20, 5);
+        let mut events = type_text("hello");
+        events.push(key(KeyCode::Enter, KeyModifiers::NONE));
+
+        let result = terminal.read_line(&mut line_editor(), events).unwrap();
+        assert!(matches!(result, LineEditorResult::Success(line) if line == "hello"));
+        assert_eq!(terminal.screen(), "> hello");
+        assert_eq!(terminal.cursor(), (7, 0));
+        assert!(terminal.ansi().contains("\x1b[1;3H"));
+    }
+
+    #[test]
+    fn render_edited_line() {
+        let mut terminal = HeadlessTerminal::new(20, 5);
+        let mut events = type_text("hallo");
+        events.push(key(KeyCode::Left, KeyModifiers::NONE));
+        events.push(key(KeyCode::Left, KeyModifiers::NONE));
+        events.push(key(KeyCode::Left, KeyModifiers::NONE));
+        events.push(key(KeyCode::Backspace, KeyModifiers::NONE));
+        events.push(key(KeyCode::Char('e'), KeyModifiers::NONE));
+        events.push(key(KeyCode::Enter, KeyModifiers::NONE));
+
+        let result = terminal.read_line(&mut line_editor(), events).unwrap();
+        assert!(matches!(result, LineEditorResult::Success(line) if line == "hello"));
+        assert_eq!(terminal.screen(), "> hello");
+        assert_eq!(terminal.cursor(), (7, 0));
+    }
+
+    #[test]
+    fn wrap_long_line() {
+        let mut terminal = HeadlessTerminal::new(8, 5);
+        let mut events = type_text("abcdefghij");
+        events.push(key(KeyCode::Enter, KeyModifiers::NONE));
+
+        terminal.read_line(&mut line_editor(), events).unwrap();
+        assert_eq!(terminal.screen(), "> abcdef\nghij");
+    }
+
+    #[test]
+    fn events_end_before_the_line() {
+        let mut terminal = HeadlessTerminal::new(20, 5);
+        let error = terminal
+            .read_line(&mut line_editor(), type_text("abc"))
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(terminal.screen(), "> abc");
+        assert!(!base::is_headless());
+    }
+
+    #[test]
+    fn screen_replays_escape_sequences() {
+        let mut screen = Screen::new((6, 3));
+        screen.write("abcdefgh\x1b[1;3H\x1b[Kx\x1b[2;1H世界");
+        let rows: Vec<String> = screen.cells.iter().map(|row| row.concat()).collect();
+        assert_eq!(rows, vec!["abx   ", "世界  ", "      "]);
+        assert_eq!(screen.cursor, (4, 1));
+    }
+}
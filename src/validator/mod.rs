@@ -0,0 +1,17 @@
+use crate::styled_buffer::StyledBuffer;
+
+/// The result of validating the current buffer when Enter is pressed
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValidationResult {
+    /// The buffer is complete and can be submitted
+    Complete,
+    /// The buffer is incomplete, Enter inserts a new line instead of submitting
+    Incomplete,
+}
+
+/// The Validator trait, Implementers of this trait will take the current styled buffer and
+/// decide if it can be submitted, for example to keep editing while a bracket is not closed
+pub trait Validator {
+    /// The action that will check the current styled buffer before submitting it
+    fn validate(&self, buffer: &StyledBuffer) -> ValidationResult;
+}
@@ -1,21 +1,163 @@
-use std::io::BufWriter;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::IsTerminal;
+use std::io::Read;
 use std::io::Result;
-use std::io::Stderr;
+use std::io::Write;
+use std::time::Duration;
 
+use crossterm::style::Attribute;
 use crossterm::style::Color;
 use crossterm::style::Print;
 use crossterm::style::SetAttribute;
 use crossterm::style::SetBackgroundColor;
 use crossterm::style::SetForegroundColor;
+use crossterm::terminal;
 use crossterm::QueueableCommand;
 
 use crate::styled_buffer::StyledBuffer;
+use crate::Output;
 
-pub fn render_styled_buffer(stdout: &mut BufWriter<Stderr>, buffer: &StyledBuffer) -> Result<()> {
+/// Maximum time to wait for the terminal to reply to the cursor position query
+const CURSOR_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+thread_local! {
+    static CURSOR_POSITION: Cell<Option<(u16, u16)>> = const { Cell::new(None) };
+    static HEADLESS_SIZE: Cell<Option<(u16, u16)>> = const { Cell::new(None) };
+    static QUERY_OUTPUT: RefCell<Option<Output>> = const { RefCell::new(None) };
+}
+
+/// Get the terminal size (columns, rows), or the size of the headless terminal if set
+pub fn terminal_size() -> Result<(u16, u16)> {
+    match HEADLESS_SIZE.get() {
+        Some(size) => Ok(size),
+        None => terminal::size(),
+    }
+}
+
+/// Set the size of a headless terminal to render without a real terminal, or None to use
+/// the real one, the raw mode is not enabled and the cursor position is never queried
+/// while it is set
+#[cfg(any(test, feature = "testing"))]
+pub fn set_headless_size(size: Option<(u16, u16)>) {
+    HEADLESS_SIZE.set(size);
+}
+
+/// Returns true if rendering to a headless terminal
+pub fn is_headless() -> bool {
+    HEADLESS_SIZE.get().is_some()
+}
+
+/// Get the cursor position (column, row)
+///
+/// The position query is written to the standard output, so when it is not a terminal,
+/// for example redirected to a file, the query is written to the output set with
+/// [`set_cursor_query_output`] and the reply is read from `/dev/tty`, if there is no output
+/// or no reply the last position recorded by the views is used instead, or the first column
+/// of the last row if there is none
+///
+/// A headless terminal starts at the top left corner
+pub fn cursor_position() -> Result<(u16, u16)> {
+    if is_headless() {
+        return Ok(CURSOR_POSITION.get().unwrap_or((0, 0)));
+    }
+
+    if std::io::stdout().is_terminal() {
+        return crossterm::cursor::position();
+    }
+
+    let output = QUERY_OUTPUT.with_borrow(|output| output.clone());
+    if let (Some(mut output), Ok(tty)) = (output, std::fs::File::open("/dev/tty")) {
+        match query_cursor_position(&mut output, tty) {
+            Ok(position) => return Ok(position),
+            // A terminal that does not reply is not queried again for this line
+            Err(_) => set_cursor_query_output(None),
+        }
+    }
+
+    if let Some(position) = CURSOR_POSITION.get() {
+        return Ok(position);
+    }
+
+    let (_, rows) = terminal::size()?;
+    Ok((0, rows.saturating_sub(1)))
+}
+
+/// Set the output the cursor position query is written to when the standard output
+/// is not a terminal, the output of the line editor while it reads a line, or None
+pub fn set_cursor_query_output(output: Option<Output>) {
+    QUERY_OUTPUT.set(output);
+}
+
+/// Write the cursor position query to the output and read the reply of the terminal input
+fn query_cursor_position(
+    output: &mut impl Write,
+    input: impl Read + Send + 'static,
+) -> Result<(u16, u16)> {
+    output.write_all(b"\x1b[6n")?;
+    output.flush()?;
+
+    // A terminal that never replies would block the read forever, so it is done
+    // on another thread and abandoned after the timeout
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(read_cursor_position_reply(input));
+    });
+    receiver
+        .recv_timeout(CURSOR_QUERY_TIMEOUT)
+        .map_err(|_| Error::new(ErrorKind::TimedOut, "No reply to the cursor position query"))?
+}
+
+/// Read the `ESC [ row ; column R` reply to the cursor position query, the bytes before
+/// the reply are skipped, the returned position starts at zero
+fn read_cursor_position_reply(mut input: impl Read) -> Result<(u16, u16)> {
+    let invalid = || Error::new(ErrorKind::InvalidData, "Invalid cursor position reply");
+    let mut reply = vec![];
+    let mut byte = [0; 1];
+
+    // One byte at a time, the keys typed after the reply are left for the line editor
+    while input.read(&mut byte)? == 1 {
+        let byte = byte[0];
+        if byte == b'\x1b' {
+            reply.clear();
+        }
+        reply.push(byte);
+        if byte == b'R' && reply.starts_with(b"\x1b[") {
+            let text = std::str::from_utf8(&reply[2..reply.len() - 1]).map_err(|_| invalid())?;
+            let (row, column) = text.split_once(';').ok_or_else(invalid)?;
+            let row: u16 = row.parse().map_err(|_| invalid())?;
+            let column: u16 = column.parse().map_err(|_| invalid())?;
+            return Ok((column.saturating_sub(1), row.saturating_sub(1)));
+        }
+    }
+    Err(Error::new(
+        ErrorKind::UnexpectedEof,
+        "No reply to the cursor position query",
+    ))
+}
+
+/// Record where a view moved the cursor, or None when it is not known anymore
+pub fn record_cursor_position(position: Option<(u16, u16)>) {
+    CURSOR_POSITION.set(position);
+}
+
+pub fn render_styled_buffer(stdout: &mut impl Write, buffer: &StyledBuffer) -> Result<()> {
+    render_styled_range(stdout, buffer, 0, buffer.len())
+}
+
+/// Render the characters of the buffer from start until end, end is not included
+pub fn render_styled_range(
+    stdout: &mut impl Write,
+    buffer: &StyledBuffer,
+    start: usize,
+    end: usize,
+) -> Result<()> {
     let styles = buffer.styles();
-    let buffer_len = buffer.len();
+    let end = usize::min(end, buffer.len());
 
-    for (i, style) in styles.iter().enumerate().take(buffer_len) {
+    for (i, style) in styles.iter().enumerate().take(end).skip(start) {
         // Set foreground Color if exists
         if let Some(color) = style.foreground_color() {
             stdout.queue(SetForegroundColor(*color))?;
@@ -31,11 +173,52 @@ pub fn render_styled_buffer(stdout: &mut BufWriter<Stderr>, buffer: &StyledBuffe
             stdout.queue(SetAttribute(*attribute))?;
         }
 
-        // Reset Colors
         stdout.queue(Print(buffer.char_at(i).unwrap()))?;
+
+        // Reset Colors and Attributes
         stdout.queue(SetForegroundColor(Color::Reset))?;
         stdout.queue(SetBackgroundColor(Color::Reset))?;
+        if !style.attributes().is_empty() {
+            stdout.queue(SetAttribute(Attribute::Reset))?;
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_reply_of_the_cursor_position_query() {
+        let reply = read_cursor_position_reply(&b"\x1b[5;12R"[..]).unwrap();
+        assert_eq!(reply, (11, 4));
+
+        // Typed keys before the reply are skipped
+        let reply = read_cursor_position_reply(&b"ab\x1b[A\x1b[1;1Rcd"[..]).unwrap();
+        assert_eq!(reply, (0, 0));
+    }
+
+    #[test]
+    fn invalid_reply_of_the_cursor_position_query() {
+        let error = read_cursor_position_reply(&b"\x1b[5R"[..]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        let error = read_cursor_position_reply(&b"\x1b[5;"[..]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn query_cursor_position_through_the_output() {
+        // Like stdout redirected to a file, the query goes to the line editor output
+        // and the reply comes from the terminal input
+        let mut output = vec![];
+        let input = std::io::Cursor::new(b"\x1b[24;3R".to_vec());
+        assert_eq!(query_cursor_position(&mut output, input).unwrap(), (2, 23));
+        assert_eq!(output, b"\x1b[6n");
+
+        let input = std::io::Cursor::new(vec![]);
+        assert!(query_cursor_position(&mut output, input).is_err());
+    }
+}
@@ -1,13 +1,12 @@
-use std::io::stdout;
 use std::io::Result;
 use std::io::Write;
 
-use crossterm::cursor::position;
 use crossterm::cursor::MoveTo;
 use crossterm::cursor::MoveToColumn;
 use crossterm::cursor::MoveToNextLine;
 use crossterm::cursor::MoveToPreviousLine;
-use crossterm::terminal;
+use crossterm::style::Attribute;
+use crossterm::style::Color;
 use crossterm::terminal::Clear;
 use crossterm::terminal::ClearType;
 use crossterm::terminal::ScrollUp;
@@ -15,52 +14,245 @@ use crossterm::QueueableCommand;
 
 use crate::completion::Suggestion;
 use crate::style::Style;
+use crate::styled_buffer::StyledBuffer;
+use crate::view::output::Output;
 use crate::ListView;
 
+/// Number of columns between the suggestion content and its description
+const DESCRIPTION_GAP: usize = 2;
+
 #[derive(Default)]
 pub struct DropDownListView {
     elements: Vec<Suggestion>,
     focus_style: Style,
+    match_style: Option<Style>,
     focus_position: i64,
     is_visible: bool,
+    max_width: Option<usize>,
+    max_height: Option<usize>,
+    scroll_offset: usize,
+    output: Output,
+}
+
+impl DropDownListView {
+    /// Set the maximum width of a row, longer suggestions are truncated with an ellipsis
+    /// when rendered but still inserted in full, the width is also bounded by the terminal width
+    pub fn set_max_width(&mut self, max_width: usize) {
+        self.max_width = Some(max_width);
+    }
+
+    /// Set the maximum number of visible suggestions, the list scrolls to keep the focused
+    /// suggestion visible and a row with the focused position is rendered under it
+    pub fn set_max_height(&mut self, max_height: usize) {
+        self.max_height = Some(usize::max(max_height, 1));
+    }
+
+    /// Number of suggestions rendered at once
+    fn visible_len(&self) -> usize {
+        self.max_height.map_or(self.elements.len(), |max| {
+            usize::min(max, self.elements.len())
+        })
+    }
+
+    /// Move the visible window so it contains the focused suggestion
+    fn scroll_to_focus(&mut self) {
+        let focus = usize::try_from(self.focus_position).unwrap_or(0);
+        let visible_len = self.visible_len();
+        if focus < self.scroll_offset {
+            self.scroll_offset = focus;
+        } else if visible_len > 0 && focus >= self.scroll_offset + visible_len {
+            self.scroll_offset = focus + 1 - visible_len;
+        }
+    }
+
+    /// Set the style applied on top of the characters that matched the input,
+    /// the colors that are not set keep the row colors so the focus background stays visible,
+    /// the matched characters are bold by default
+    pub fn set_match_style(&mut self, style: Style) {
+        self.match_style = Some(style);
+    }
+
+    /// Width of the content column, the longest content bounded by the row width
+    /// when a suggestion has a description so the descriptions are aligned
+    fn content_width(&self, width: usize) -> usize {
+        if self.elements.iter().all(|s| s.description.is_none()) {
+            return width;
+        }
+
+        let longest = self
+            .elements
+            .iter()
+            .map(|s| s.content.len())
+            .max()
+            .unwrap_or(0);
+        usize::min(longest, width)
+    }
+
+    /// Build the styled row of a suggestion, the content padded to the content width
+    /// followed by the dimmed description truncated to the remaining width
+    fn row_buffer(
+        &self,
+        suggestion: &Suggestion,
+        is_focused: bool,
+        width: usize,
+        content_width: usize,
+    ) -> StyledBuffer {
+        let mut row = self.content_buffer(suggestion, is_focused, content_width);
+
+        let Some(description) = &suggestion.description else {
+            return row;
+        };
+
+        let description_width = width.saturating_sub(content_width + DESCRIPTION_GAP);
+        if description_width == 0 {
+            return row;
+        }
+
+        let mut style = if is_focused {
+            self.focus_style.clone()
+        } else {
+            Style::default()
+        };
+
+        while row.len() < content_width + DESCRIPTION_GAP {
+            row.insert_styled_char(' ', style.clone());
+        }
+
+        style.set_foreground_color(Color::DarkGrey);
+        style.add_attribute(Attribute::Dim);
+        if description.chars().count() > description_width {
+            let truncated: String = description.chars().take(description_width - 1).collect();
+            row.insert_styled_string(&truncated, style.clone());
+            row.insert_styled_char('…', style);
+        } else {
+            row.insert_styled_string(description, style);
+        }
+
+        row
+    }
+
+    /// Build the styled content of a suggestion, applying the focus and match styles
+    /// and truncating it to the width
+    fn content_buffer(
+        &self,
+        suggestion: &Suggestion,
+        is_focused: bool,
+        width: usize,
+    ) -> StyledBuffer {
+        let content = &suggestion.content;
+        let mut styles = content.styles().clone();
+        if is_focused {
+            styles.fill(self.focus_style.clone());
+        }
+
+        // Emphasize the characters that matched the input
+        for &position in &suggestion.match_indices {
+            if let Some(style) = styles.get_mut(position) {
+                let Some(match_style) = &self.match_style else {
+                    style.add_attribute(Attribute::Bold);
+                    continue;
+                };
+
+                if let Some(color) = match_style.foreground_color() {
+                    style.set_foreground_color(*color);
+                }
+                if let Some(color) = match_style.background_color() {
+                    style.set_background_color(*color);
+                }
+                for attribute in match_style.attributes() {
+                    style.add_attribute(*attribute);
+                }
+            }
+        }
+
+        let is_truncated = content.len() > width;
+        let visible_len = if is_truncated {
+            width.saturating_sub(1)
+        } else {
+            content.len()
+        };
+
+        let mut row = StyledBuffer::default();
+        for (position, style) in styles.into_iter().enumerate().take(visible_len) {
+            if let Some(ch) = content.char_at(position) {
+                row.insert_styled_char(ch, style);
+            }
+        }
+
+        if is_truncated && width > 0 {
+            let style = if is_focused {
+                self.focus_style.clone()
+            } else {
+                Style::default()
+            };
+            row.insert_styled_char('…', style);
+        }
+
+        row
+    }
 }
 
 impl ListView<Suggestion> for DropDownListView {
     fn render(&mut self) -> Result<()> {
-        let mut stdout = std::io::BufWriter::new(std::io::stderr());
+        let mut stdout = std::io::BufWriter::new(self.output.clone());
 
-        let (_, rows) = terminal::size()?;
-        let (start_column, start_row) = position()?;
+        let (columns, rows) = super::base::terminal_size()?;
+        let (start_column, start_row) = super::base::cursor_position()?;
+
+        self.scroll_to_focus();
+        let height = self.height() as u16;
 
         let mut number_of_scrolls = 0;
-        if (start_row + 1 + self.elements.len() as u16) > rows {
-            number_of_scrolls = (start_row + 1 + self.elements.len() as u16) - rows + 1;
+        if (start_row + 1 + height) > rows {
+            number_of_scrolls = (start_row + 1 + height) - rows + 1;
             stdout.queue(ScrollUp(number_of_scrolls))?;
             stdout.queue(MoveToPreviousLine(number_of_scrolls))?;
         }
 
-        for (index, suggestion) in self.elements.iter_mut().enumerate() {
-            let content = &mut suggestion.content;
+        let available_width = columns.saturating_sub(start_column) as usize;
+        let width = self
+            .max_width
+            .map_or(available_width, |max| usize::min(max, available_width));
+        let content_width = self.content_width(width);
+
+        let visible_elements = self
+            .elements
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(self.visible_len());
+        for (index, suggestion) in visible_elements {
             stdout.queue(MoveToNextLine(1))?;
             stdout.queue(MoveToColumn(start_column))?;
 
-            if index as i64 == self.focus_position {
-                let mut current_styles = content.styles().clone();
-                content.style_all(self.focus_style.clone());
-                super::base::render_styled_buffer(&mut stdout, content)?;
-                content.set_styles(&mut current_styles);
-            } else {
-                super::base::render_styled_buffer(&mut stdout, content)?;
-            }
+            let is_focused = index as i64 == self.focus_position;
+            let row = self.row_buffer(suggestion, is_focused, width, content_width);
+            super::base::render_styled_buffer(&mut stdout, &row)?;
         }
 
-        stdout.queue(MoveTo(start_column, start_row - number_of_scrolls))?;
+        // Scroll indicator with the focused position when not all suggestions are visible
+        if self.visible_len() < self.elements.len() {
+            stdout.queue(MoveToNextLine(1))?;
+            stdout.queue(MoveToColumn(start_column))?;
+
+            let mut style = Style::default();
+            style.set_foreground_color(Color::DarkGrey);
+            let indicator = format!("{} of {}", self.focus_position + 1, self.elements.len());
+            let mut row = StyledBuffer::default();
+            row.insert_styled_string(&indicator, style);
+            super::base::render_styled_buffer(&mut stdout, &row)?;
+        }
+
+        // A list taller than the terminal scrolls the start row out of the screen
+        let start_row = start_row.saturating_sub(number_of_scrolls);
+        stdout.queue(MoveTo(start_column, start_row))?;
+        super::base::record_cursor_position(Some((start_column, start_row)));
         stdout.flush()?;
         Ok(())
     }
 
     fn clear(&self) -> Result<()> {
-        let mut stdout = stdout();
+        let mut stdout = self.output.clone();
         stdout.queue(Clear(ClearType::FromCursorDown))?;
         stdout.flush()?;
         Ok(())
@@ -82,6 +274,10 @@ impl ListView<Suggestion> for DropDownListView {
         self.focus_style = style;
     }
 
+    fn set_output(&mut self, output: Output) {
+        self.output = output;
+    }
+
     fn focus_next(&mut self) {
         if self.focus_position < self.elements.len() as i64 - 1 {
             self.focus_position += 1;
@@ -96,6 +292,7 @@ impl ListView<Suggestion> for DropDownListView {
 
     fn clear_focus(&mut self) {
         self.focus_position = 0;
+        self.scroll_offset = 0;
     }
 
     fn reset(&mut self) {
@@ -122,4 +319,182 @@ impl ListView<Suggestion> for DropDownListView {
     fn len(&self) -> usize {
         self.elements.len()
     }
+
+    fn height(&self) -> usize {
+        let visible_len = self.visible_len();
+        if visible_len < self.elements.len() {
+            visible_len + 1
+        } else {
+            visible_len
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crossterm::style::Print;
+    use crossterm::style::SetBackgroundColor;
+    use crossterm::style::SetForegroundColor;
+
+    use super::*;
+    use crate::completion::Span;
+    use crate::testing::SharedOutput;
+    use crate::view::base;
+
+    fn suggestion(content: &str, match_indices: Vec<usize>) -> Suggestion {
+        let mut suggestion = Suggestion::new(StyledBuffer::from(content), Span::new(0, 0));
+        suggestion.match_indices = match_indices;
+        suggestion
+    }
+
+    fn list_view(elements: Vec<Suggestion>) -> DropDownListView {
+        let mut list_view = DropDownListView::default();
+        let mut focus_style = Style::default();
+        focus_style.set_background_color(Color::Blue);
+        list_view.set_focus_style(focus_style);
+        list_view.set_elements(&mut elements.into_iter().collect());
+        list_view
+    }
+
+    /// Render the list under the cursor at the top left corner of a headless terminal
+    fn render(list_view: &mut DropDownListView, size: (u16, u16)) -> String {
+        render_at(list_view, size, 0)
+    }
+
+    /// Render the list under the cursor at the start of the row of a headless terminal
+    fn render_at(list_view: &mut DropDownListView, size: (u16, u16), row: u16) -> String {
+        let bytes = Rc::new(RefCell::new(vec![]));
+        list_view.set_output(Output::new(Box::new(SharedOutput(bytes.clone()))));
+
+        base::set_headless_size(Some(size));
+        base::record_cursor_position(Some((0, row)));
+        let result = list_view.render();
+        base::set_headless_size(None);
+        base::record_cursor_position(None);
+
+        result.unwrap();
+        String::from_utf8(bytes.take()).unwrap()
+    }
+
+    /// The ANSI sequence of a character with the colors set before it
+    fn colored_char(ch: char, foreground: Option<Color>, background: Option<Color>) -> String {
+        let mut bytes: Vec<u8> = vec![];
+        if let Some(color) = foreground {
+            bytes.queue(SetForegroundColor(color)).unwrap();
+        }
+        if let Some(color) = background {
+            bytes.queue(SetBackgroundColor(color)).unwrap();
+        }
+        bytes.queue(Print(ch)).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn render_match_style_on_focused_and_unfocused_rows() {
+        let mut list_view = list_view(vec![
+            suggestion("apple", vec![0, 1]),
+            suggestion("apricot", vec![0, 1]),
+        ]);
+        let mut match_style = Style::default();
+        match_style.set_foreground_color(Color::Red);
+        list_view.set_match_style(match_style);
+        list_view.set_focus_position(0);
+
+        let ansi = render(&mut list_view, (20, 10));
+        let focused_match = colored_char('a', Some(Color::Red), Some(Color::Blue));
+        let focused_rest = colored_char('l', None, Some(Color::Blue));
+        let unfocused_match = colored_char('p', Some(Color::Red), None);
+        assert!(ansi.contains(&focused_match));
+        assert!(ansi.contains(&focused_rest));
+        assert!(ansi.contains(&unfocused_match));
+
+        let focused = list_view.content_buffer(&list_view.elements[0], true, 20);
+        let styles = focused.styles();
+        assert_eq!(*styles[1].foreground_color(), Some(Color::Red));
+        assert_eq!(*styles[1].background_color(), Some(Color::Blue));
+        assert_eq!(*styles[2].foreground_color(), None);
+        assert_eq!(*styles[2].background_color(), Some(Color::Blue));
+
+        let unfocused = list_view.content_buffer(&list_view.elements[1], false, 20);
+        let styles = unfocused.styles();
+        assert_eq!(*styles[0].foreground_color(), Some(Color::Red));
+        assert_eq!(*styles[0].background_color(), None);
+        assert_eq!(*styles[2].foreground_color(), None);
+    }
+
+    #[test]
+    fn matched_characters_are_bold_without_match_style() {
+        let list_view = list_view(vec![suggestion("apple", vec![0])]);
+        let row = list_view.content_buffer(&list_view.elements[0], true, 20);
+        assert_eq!(row.styles()[0].attributes(), &vec![Attribute::Bold]);
+        assert_eq!(*row.styles()[0].background_color(), Some(Color::Blue));
+        assert!(row.styles()[1].attributes().is_empty());
+    }
+
+    fn words(words: &[&str]) -> Vec<Suggestion> {
+        words.iter().map(|word| suggestion(word, vec![])).collect()
+    }
+
+    #[test]
+    fn scroll_to_the_focused_suggestion() {
+        let mut list_view = list_view(words(&["a1", "a2", "a3", "a4", "a5", "a6"]));
+        list_view.set_max_height(3);
+        assert_eq!(list_view.height(), 4);
+
+        let screen = crate::testing::replay((20, 10), &render(&mut list_view, (20, 10)));
+        assert_eq!(screen, "\na1\na2\na3\n1 of 6");
+
+        for _ in 0..4 {
+            list_view.focus_next();
+        }
+        let screen = crate::testing::replay((20, 10), &render(&mut list_view, (20, 10)));
+        assert_eq!(screen, "\na3\na4\na5\n5 of 6");
+
+        // Moving back up scrolls only when the focus leaves the window
+        list_view.focus_previous();
+        list_view.focus_previous();
+        let screen = crate::testing::replay((20, 10), &render(&mut list_view, (20, 10)));
+        assert_eq!(screen, "\na3\na4\na5\n3 of 6");
+        list_view.focus_previous();
+        let screen = crate::testing::replay((20, 10), &render(&mut list_view, (20, 10)));
+        assert_eq!(screen, "\na2\na3\na4\n2 of 6");
+
+        // The last suggestion is reachable and the focus stops there
+        for _ in 0..10 {
+            list_view.focus_next();
+        }
+        assert_eq!(
+            list_view.selected_element().unwrap().content.literal(),
+            "a6"
+        );
+        let screen = crate::testing::replay((20, 10), &render(&mut list_view, (20, 10)));
+        assert_eq!(screen, "\na4\na5\na6\n6 of 6");
+    }
+
+    #[test]
+    fn no_scroll_indicator_when_all_suggestions_fit() {
+        let mut list_view = list_view(words(&["a1", "a2"]));
+        list_view.set_max_height(3);
+        assert_eq!(list_view.height(), 2);
+        let screen = crate::testing::replay((20, 10), &render(&mut list_view, (20, 10)));
+        assert_eq!(screen, "\na1\na2");
+    }
+
+    #[test]
+    fn scroll_the_terminal_when_the_list_does_not_fit_under_the_cursor() {
+        let mut list_view = list_view(words(&["a1", "a2", "a3", "a4", "a5", "a6"]));
+        list_view.set_max_height(3);
+        let ansi = render_at(&mut list_view, (20, 6), 4);
+        assert!(ansi.contains("\x1b[4S"));
+        assert!(ansi.ends_with("\x1b[1;1H"));
+
+        // The list as tall as the terminal scrolls the start row out of the screen
+        let ansi = render_at(&mut list_view, (20, 5), 4);
+        assert!(ansi.ends_with("\x1b[1;1H"));
+        let screen = crate::testing::replay((20, 5), &format!("\x1b[5;1H{ansi}"));
+        assert_eq!(screen, "\na1\na2\na3\n1 of 6");
+    }
 }
@@ -0,0 +1,255 @@
+use std::io::Result;
+use std::io::Write;
+
+use crossterm::cursor::MoveTo;
+use crossterm::cursor::MoveToColumn;
+use crossterm::cursor::MoveToNextLine;
+use crossterm::cursor::MoveToPreviousLine;
+use crossterm::style::Attribute;
+use crossterm::terminal::Clear;
+use crossterm::terminal::ClearType;
+use crossterm::terminal::ScrollUp;
+use crossterm::QueueableCommand;
+
+use crate::completion::Suggestion;
+use crate::style::Style;
+use crate::styled_buffer::StyledBuffer;
+use crate::view::output::Output;
+use crate::ListView;
+
+/// Number of columns between two cells
+const CELL_GAP: usize = 2;
+
+/// List view that lays the suggestions out in columns sized to the terminal width,
+/// row by row, so large sets of short suggestions take fewer rows
+///
+/// Up and Down move across rows and Left and Right move across columns,
+/// descriptions are not rendered
+pub struct GridListView {
+    elements: Vec<Suggestion>,
+    focus_style: Style,
+    focus_position: i64,
+    is_visible: bool,
+    columns: usize,
+    output: Output,
+}
+
+impl Default for GridListView {
+    fn default() -> Self {
+        GridListView {
+            elements: vec![],
+            focus_style: Style::default(),
+            focus_position: 0,
+            is_visible: false,
+            columns: 1,
+            output: Output::default(),
+        }
+    }
+}
+
+impl GridListView {
+    /// Width of a cell without the gap, the longest suggestion bounded by the terminal width
+    fn cell_width(&self, terminal_width: usize) -> usize {
+        let longest = self
+            .elements
+            .iter()
+            .map(|s| s.content.len())
+            .max()
+            .unwrap_or(0);
+        usize::max(usize::min(longest, terminal_width), 1)
+    }
+
+    /// Number of rows needed to render all the suggestions in the current columns
+    fn rows(&self) -> usize {
+        self.elements.len().div_ceil(self.columns)
+    }
+
+    /// Build the styled cell of a suggestion, padded or truncated to the width
+    fn cell_buffer(&self, suggestion: &Suggestion, is_focused: bool, width: usize) -> StyledBuffer {
+        let content = &suggestion.content;
+        let mut styles = content.styles().clone();
+        if is_focused {
+            styles.fill(self.focus_style.clone());
+        }
+
+        // Emphasize the characters that matched the input
+        for &position in &suggestion.match_indices {
+            if let Some(style) = styles.get_mut(position) {
+                style.add_attribute(Attribute::Bold);
+            }
+        }
+
+        let is_truncated = content.len() > width;
+        let visible_len = if is_truncated {
+            width.saturating_sub(1)
+        } else {
+            content.len()
+        };
+
+        let mut cell = StyledBuffer::default();
+        for (position, style) in styles.into_iter().enumerate().take(visible_len) {
+            if let Some(ch) = content.char_at(position) {
+                cell.insert_styled_char(ch, style);
+            }
+        }
+
+        let padding_style = if is_focused {
+            self.focus_style.clone()
+        } else {
+            Style::default()
+        };
+
+        if is_truncated {
+            cell.insert_styled_char('…', padding_style.clone());
+        }
+
+        while cell.len() < width {
+            cell.insert_styled_char(' ', padding_style.clone());
+        }
+
+        cell
+    }
+}
+
+impl ListView<Suggestion> for GridListView {
+    fn render(&mut self) -> Result<()> {
+        let mut stdout = std::io::BufWriter::new(self.output.clone());
+
+        let (columns, rows) = super::base::terminal_size()?;
+        let (start_column, start_row) = super::base::cursor_position()?;
+
+        let terminal_width = columns as usize;
+        let cell_width = self.cell_width(terminal_width);
+        self.columns = usize::max((terminal_width + CELL_GAP) / (cell_width + CELL_GAP), 1);
+
+        let height = self.height() as u16;
+
+        let mut number_of_scrolls = 0;
+        if (start_row + 1 + height) > rows {
+            number_of_scrolls = (start_row + 1 + height) - rows + 1;
+            stdout.queue(ScrollUp(number_of_scrolls))?;
+            stdout.queue(MoveToPreviousLine(number_of_scrolls))?;
+        }
+
+        for (row_index, suggestions) in self.elements.chunks(self.columns).enumerate() {
+            stdout.queue(MoveToNextLine(1))?;
+
+            for (column_index, suggestion) in suggestions.iter().enumerate() {
+                let index = row_index * self.columns + column_index;
+                let is_focused = index as i64 == self.focus_position;
+                let cell = self.cell_buffer(suggestion, is_focused, cell_width);
+
+                stdout.queue(MoveToColumn(
+                    (column_index * (cell_width + CELL_GAP)) as u16,
+                ))?;
+                super::base::render_styled_buffer(&mut stdout, &cell)?;
+            }
+        }
+
+        stdout.queue(MoveTo(start_column, start_row - number_of_scrolls))?;
+        super::base::record_cursor_position(Some((start_column, start_row - number_of_scrolls)));
+        stdout.flush()?;
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        let mut stdout = self.output.clone();
+        stdout.queue(Clear(ClearType::FromCursorDown))?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    fn set_visibility(&mut self, visible: bool) {
+        self.is_visible = visible;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+
+    fn set_focus_position(&mut self, position: i64) {
+        self.focus_position = position;
+    }
+
+    fn set_focus_style(&mut self, style: Style) {
+        self.focus_style = style;
+    }
+
+    fn set_output(&mut self, output: Output) {
+        self.output = output;
+    }
+
+    fn focus_next(&mut self) {
+        if self.focus_position < self.elements.len() as i64 - 1 {
+            self.focus_position += 1;
+        }
+    }
+
+    fn focus_previous(&mut self) {
+        if self.focus_position > 0 {
+            self.focus_position -= 1;
+        }
+    }
+
+    fn focus_up(&mut self) {
+        let columns = self.columns as i64;
+        if self.focus_position >= columns {
+            self.focus_position -= columns;
+        }
+    }
+
+    fn focus_down(&mut self) {
+        let columns = self.columns as i64;
+        if self.focus_position + columns < self.elements.len() as i64 {
+            self.focus_position += columns;
+        }
+    }
+
+    fn focus_left(&mut self) -> bool {
+        if self.focus_position % self.columns as i64 > 0 {
+            self.focus_position -= 1;
+        }
+        true
+    }
+
+    fn focus_right(&mut self) -> bool {
+        let is_last_column = self.focus_position % self.columns as i64 == self.columns as i64 - 1;
+        if !is_last_column {
+            self.focus_next();
+        }
+        true
+    }
+
+    fn clear_focus(&mut self) {
+        self.focus_position = 0;
+    }
+
+    fn reset(&mut self) {
+        self.clear_elements();
+        self.clear_focus();
+    }
+
+    fn set_elements(&mut self, elements: &mut Vec<Suggestion>) {
+        self.elements.append(elements);
+    }
+
+    fn clear_elements(&mut self) {
+        self.elements.clear();
+    }
+
+    fn selected_element(&self) -> Option<&Suggestion> {
+        self.elements.get(self.focus_position as usize)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    fn height(&self) -> usize {
+        self.rows()
+    }
+}
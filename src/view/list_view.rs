@@ -1,6 +1,7 @@
 use std::io::Result;
 
 use crate::style::Style;
+use crate::view::output::Output;
 
 pub trait ListView<T> {
     fn render(&mut self) -> Result<()>;
@@ -10,9 +11,34 @@ pub trait ListView<T> {
 
     fn set_focus_position(&mut self, position: i64);
     fn set_focus_style(&mut self, style: Style);
+
+    /// Set the target of the rendered list, the list views of the line editor share its output
+    fn set_output(&mut self, _output: Output) {}
     fn focus_next(&mut self);
     fn focus_previous(&mut self);
     fn clear_focus(&mut self);
+
+    /// Move the focus to the element above, the previous element for a single column list
+    fn focus_up(&mut self) {
+        self.focus_previous();
+    }
+
+    /// Move the focus to the element below, the next element for a single column list
+    fn focus_down(&mut self) {
+        self.focus_next();
+    }
+
+    /// Move the focus to the element on the left,
+    /// returns false if the list has no columns so the key can move the cursor instead
+    fn focus_left(&mut self) -> bool {
+        false
+    }
+
+    /// Move the focus to the element on the right,
+    /// returns false if the list has no columns so the key can move the cursor instead
+    fn focus_right(&mut self) -> bool {
+        false
+    }
     fn reset(&mut self);
 
     fn set_elements(&mut self, elements: &mut Vec<T>);
@@ -20,4 +46,10 @@ pub trait ListView<T> {
     fn selected_element(&self) -> Option<&T>;
     fn is_empty(&self) -> bool;
     fn len(&self) -> usize;
+
+    /// Number of rows used to render the list, it can be less than the number of elements
+    /// for a list that scrolls
+    fn height(&self) -> usize {
+        self.len()
+    }
 }
@@ -1,4 +1,6 @@
 pub mod base;
 pub mod drop_down_list_view;
+pub mod grid_list_view;
 pub mod list_view;
+pub mod output;
 pub mod styled_editor_view;
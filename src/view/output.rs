@@ -0,0 +1,38 @@
+use std::cell::RefCell;
+use std::io::Result;
+use std::io::Write;
+use std::rc::Rc;
+
+/// The output target shared by the line editor views, every clone writes to the same target
+///
+/// The prompt, the line, the suggestions and the terminal control sequences are written
+/// to the standard error by default
+#[derive(Clone)]
+pub struct Output {
+    target: Rc<RefCell<Box<dyn Write>>>,
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Output::new(Box::new(std::io::stderr()))
+    }
+}
+
+impl Output {
+    /// Create instance of Output that writes to the target
+    pub fn new(target: Box<dyn Write>) -> Self {
+        Output {
+            target: Rc::new(RefCell::new(target)),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.target.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.target.borrow_mut().flush()
+    }
+}
@@ -3,51 +3,578 @@ use std::io::Write;
 
 use crossterm::cursor;
 use crossterm::cursor::SetCursorStyle;
+use crossterm::style::Attribute;
+use crossterm::style::Color;
+use crossterm::style::Print;
 use crossterm::terminal;
 use crossterm::QueueableCommand;
+use unicode_width::UnicodeWidthChar;
+use unicode_width::UnicodeWidthStr;
 
+use crate::core::style::Style;
 use crate::core::styled_buffer::StyledBuffer;
 use crate::view;
+use crate::view::output::Output;
+
+/// The direction used to lay out the line on the terminal
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextDirection {
+    /// Render the line from the prompt to the right, the default
+    LeftToRight,
+    /// Render the line right aligned and reversed, for languages like Arabic and Hebrew
+    RightToLeft,
+}
 
 pub struct StyledEditorView {
-    stdout: std::io::BufWriter<std::io::Stderr>,
+    stdout: std::io::BufWriter<Output>,
     start_position: (u16, u16),
+    continuation_prompt: String,
+    continuation_prompt_width: u16,
+    right_prompt: Option<StyledBuffer>,
     terminal_size: (u16, u16),
+    text_direction: TextDirection,
+    mask: Option<char>,
+    hint_style: Style,
+    horizontal_scroll: bool,
+    scroll_offset: usize,
+    scroll_window: Option<ScrollWindow>,
+    rendered_line: Option<RenderedLine>,
+    /// Row relative to the start row where the last line render left the cursor
+    cursor_row: u16,
+}
+
+/// The part of the buffer rendered by the last horizontally scrolled render
+#[derive(Clone, Copy)]
+struct ScrollWindow {
+    /// Buffer position of the first rendered character
+    start: usize,
+    /// Buffer position after the last rendered character
+    end: usize,
+    /// Column of the first rendered character
+    column: u16,
+}
+
+/// The characters and the styles of the last line render, to render only the changed cells
+struct RenderedLine {
+    chars: Vec<char>,
+    styles: Vec<Style>,
+    /// Row relative to the start row of the end of the line, or of the hint rendered after it
+    rows: u16,
 }
 
 impl Default for StyledEditorView {
     fn default() -> Self {
+        let mut hint_style = Style::default();
+        hint_style.set_foreground_color(Color::DarkGrey);
+        hint_style.add_attribute(Attribute::Dim);
+
         Self {
-            stdout: std::io::BufWriter::new(std::io::stderr()),
+            stdout: std::io::BufWriter::new(Output::default()),
             start_position: (0, 0),
-            terminal_size: terminal::size().unwrap_or((0, 0)),
+            continuation_prompt: String::new(),
+            continuation_prompt_width: 0,
+            right_prompt: None,
+            terminal_size: view::base::terminal_size().unwrap_or((0, 0)),
+            text_direction: TextDirection::LeftToRight,
+            mask: None,
+            hint_style,
+            horizontal_scroll: false,
+            scroll_offset: 0,
+            scroll_window: None,
+            rendered_line: None,
+            cursor_row: 0,
         }
     }
 }
 
 impl StyledEditorView {
     /// Render the current line styled buffer
+    ///
+    /// Only the cells that changed since the last render are written again, the whole line
+    /// is rendered after the prompt is rendered or the layout is changed
     pub fn render_line_buffer(&mut self, buffer: &StyledBuffer) -> Result<()> {
-        let buffer_position = buffer.position() as u16;
+        self.scroll_window = None;
+        let previous_line = self.rendered_line.take();
 
-        // Move to the start position, exactly after the prompt
-        self.stdout
-            .queue(cursor::MoveToRow(self.start_position.1))?;
-        self.stdout
-            .queue(cursor::MoveToColumn(self.start_position.0))?;
+        let is_scrolled = self.horizontal_scroll
+            && self.text_direction == TextDirection::LeftToRight
+            && !buffer.literal().contains('\n');
+        if is_scrolled || self.text_direction == TextDirection::RightToLeft {
+            // Move to the start position, exactly after the prompt and clear the line
+            self.stdout
+                .queue(cursor::MoveTo(self.start_position.0, self.start_position.1))?;
+            self.stdout
+                .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        }
 
-        // Clear line
-        self.stdout
-            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        if is_scrolled {
+            self.render_scrolled_buffer(buffer)?;
+            self.flush()?;
+            return Ok(());
+        }
+
+        // The cursor is still placed using the real buffer, the mask has the same layout
+        let masked_buffer = self.mask.map(|mask| masked_buffer(buffer, mask));
+        let rendered_buffer = masked_buffer.as_ref().unwrap_or(buffer);
+
+        if self.text_direction == TextDirection::RightToLeft {
+            self.render_right_to_left_buffer(rendered_buffer)?;
+            self.flush()?;
+            return Ok(());
+        }
+
+        let line = RenderedLine {
+            chars: rendered_buffer.literal().chars().collect(),
+            styles: rendered_buffer.styles().clone(),
+            rows: self
+                .cursor_location(rendered_buffer, rendered_buffer.len())
+                .1,
+        };
+        match previous_line {
+            Some(previous) if previous.chars == line.chars => {
+                self.render_changed_styles(rendered_buffer, &previous)?;
+            }
+            Some(previous) => {
+                let unchanged = previous
+                    .chars
+                    .iter()
+                    .zip(&previous.styles)
+                    .zip(line.chars.iter().zip(&line.styles))
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                self.render_from(rendered_buffer, unchanged, Some(previous.rows))?;
+            }
+            None => self.render_from(rendered_buffer, 0, None)?,
+        }
+        self.rendered_line = Some(line);
 
-        view::base::render_styled_buffer(&mut self.stdout, buffer)?;
+        // The terminal scrolls up when the buffer is printed past the last row
+        let (_, last_row) = self.cursor_location(buffer, buffer.len());
+        let max_row = self.terminal_size.1.saturating_sub(1);
+        if self.terminal_size.1 > 0 && self.start_position.1 + last_row > max_row {
+            self.start_position.1 = max_row.saturating_sub(last_row);
+        }
 
         // Move the cursor to the current insertion position
-        self.update_cursor_position(buffer_position)?;
+        let (column, row) = self.cursor_location(buffer, buffer.position());
+        self.move_to_location((column, row))?;
+        view::base::record_cursor_position(Some((column, self.start_position.1 + row)));
         self.flush()?;
         Ok(())
     }
 
+    /// Clear the line from the grapheme cluster at or before the buffer position and
+    /// render the buffer from there
+    ///
+    /// Without the last row of the previous render the whole line is rendered from the start
+    /// position and everything below it is cleared. Otherwise the cursor is moved from where
+    /// the last render left it and only the rows of the previous line are cleared, keeping
+    /// the views rendered below the line like the suggestions
+    fn render_from(
+        &mut self,
+        buffer: &StyledBuffer,
+        position: usize,
+        previous_rows: Option<u16>,
+    ) -> Result<()> {
+        let boundaries = buffer.grapheme_boundaries();
+        let mut start = boundaries
+            .iter()
+            .copied()
+            .take_while(|bound| *bound <= position)
+            .last()
+            .unwrap_or(0);
+
+        let Some(previous_rows) = previous_rows else {
+            let (column, row) = self.resume_location(buffer, start);
+            self.stdout
+                .queue(cursor::MoveTo(column, self.start_position.1 + row))?;
+            self.stdout
+                .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
+            return self.render_rows(buffer, start);
+        };
+
+        // Moving down does not scroll the terminal, the cluster printed into the last column
+        // is printed again to wrap the line to a row that may not exist yet
+        let width = self.terminal_size.0;
+        if start > 0 && width > 0 && self.layout_location(buffer, start).0 >= width {
+            start = boundaries
+                .iter()
+                .copied()
+                .take_while(|bound| *bound < start)
+                .last()
+                .unwrap_or(0);
+        }
+
+        let location = self.resume_location(buffer, start);
+        self.move_to_location(location)?;
+        self.stdout
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        for row in location.1 + 1..=previous_rows {
+            self.move_to_location((0, row))?;
+            self.stdout
+                .queue(terminal::Clear(terminal::ClearType::CurrentLine))?;
+        }
+        self.move_to_location(location)?;
+        self.render_rows(buffer, start)
+    }
+
+    /// Render the buffer from the position where the cursor is, every line after the first one
+    /// starts with the continuation prompt
+    fn render_rows(&mut self, buffer: &StyledBuffer, start: usize) -> Result<()> {
+        // Every line after the first one starts with the continuation prompt,
+        // raw mode does not return to the first column on a new line
+        let mut line_start = start;
+        for i in start..buffer.len() {
+            if buffer.char_at(i) == Some('\n') {
+                view::base::render_styled_range(&mut self.stdout, buffer, line_start, i)?;
+                self.stdout.queue(Print("\r\n"))?;
+                self.stdout.queue(Print(&self.continuation_prompt))?;
+                line_start = i + 1;
+            }
+        }
+        let line_end = buffer.len();
+        view::base::render_styled_range(&mut self.stdout, buffer, line_start, line_end)?;
+        self.cursor_row = self.cursor_location(buffer, line_end).1;
+        Ok(())
+    }
+
+    /// Move the cursor to the column and the row relative to the start row, moving up or down
+    /// from the row where the cursor was left instead of to an absolute row, the start row may
+    /// be scrolled since the views below the line were rendered
+    fn move_to_location(&mut self, (column, row): (u16, u16)) -> Result<()> {
+        if row < self.cursor_row {
+            self.stdout.queue(cursor::MoveUp(self.cursor_row - row))?;
+        } else if row > self.cursor_row {
+            self.stdout.queue(cursor::MoveDown(row - self.cursor_row))?;
+        }
+        self.stdout.queue(cursor::MoveToColumn(column))?;
+        self.cursor_row = row;
+        Ok(())
+    }
+
+    /// Render again only the grapheme clusters whose styles changed since the previous render
+    /// of the same text, then clear the rest of the last row and the rows the hint wrapped to
+    fn render_changed_styles(
+        &mut self,
+        buffer: &StyledBuffer,
+        previous: &RenderedLine,
+    ) -> Result<()> {
+        let styles = buffer.styles();
+        let mut location = (self.start_position.0, 0);
+        let mut next_location = None;
+        for bounds in buffer.grapheme_boundaries().windows(2) {
+            let grapheme = buffer.sub_string(bounds[0], bounds[1]).unwrap_or_default();
+            let start = self.advance_location(&grapheme, &mut location);
+
+            // New lines are rendered without a style
+            let is_changed = styles[bounds[0]..bounds[1]] != previous.styles[bounds[0]..bounds[1]];
+            if grapheme == "\n" || !is_changed {
+                continue;
+            }
+
+            // Consecutive changed clusters on the same row are rendered without moving
+            if next_location != Some(start) {
+                self.move_to_location(start)?;
+            }
+            view::base::render_styled_range(&mut self.stdout, buffer, bounds[0], bounds[1])?;
+            next_location = Some(location);
+        }
+
+        // Nothing is rendered after a line that ends in the last column on the same row
+        let end = self.layout_location(buffer, buffer.len());
+        if self.terminal_size.0 == 0 || end.0 < self.terminal_size.0 {
+            self.move_to_location(end)?;
+            self.stdout
+                .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        }
+        for row in end.1 + 1..=previous.rows {
+            self.move_to_location((0, row))?;
+            self.stdout
+                .queue(terminal::Clear(terminal::ClearType::CurrentLine))?;
+        }
+        Ok(())
+    }
+
+    /// Calculate the location where the rendering continues at the buffer position, on the next
+    /// row if the previous cluster was printed into the last column
+    fn resume_location(&self, buffer: &StyledBuffer, position: usize) -> (u16, u16) {
+        let (column, row) = self.layout_location(buffer, position);
+        if self.terminal_size.0 > 0 && column >= self.terminal_size.0 {
+            return (0, row + 1);
+        }
+        (column, row)
+    }
+
+    /// Calculate the column and the row relative to the start row of the buffer position,
+    /// following new lines and wrapping at the terminal width
+    fn cursor_location(&self, buffer: &StyledBuffer, position: usize) -> (u16, u16) {
+        let mut location = self.layout_location(buffer, position);
+
+        // The terminal keeps the cursor on the last column after printing into it
+        let width = self.terminal_size.0;
+        if width > 0 && location.0 >= width {
+            location.0 = width - 1;
+        }
+        location
+    }
+
+    /// Calculate the column and the row relative to the start row where the buffer position
+    /// is laid out, the column is the terminal width after a cluster printed into the last column
+    fn layout_location(&self, buffer: &StyledBuffer, position: usize) -> (u16, u16) {
+        let mut location = (self.start_position.0, 0);
+        for bounds in buffer.grapheme_boundaries().windows(2) {
+            if bounds[0] >= position {
+                break;
+            }
+            let grapheme = buffer.sub_string(bounds[0], bounds[1]).unwrap_or_default();
+            self.advance_location(&grapheme, &mut location);
+        }
+        location
+    }
+
+    /// Move the location over the grapheme cluster and return the location where it starts,
+    /// a cluster that does not fit on the current row is wrapped to the next one
+    ///
+    /// Wide clusters like CJK characters and emoji take two terminal columns
+    fn advance_location(&self, grapheme: &str, location: &mut (u16, u16)) -> (u16, u16) {
+        if grapheme == "\n" {
+            let start = *location;
+            *location = (self.continuation_prompt_width, location.1 + 1);
+            return start;
+        }
+
+        let char_width = self.grapheme_width(grapheme) as u16;
+        let width = self.terminal_size.0;
+        if width > 0 && location.0 + char_width > width {
+            *location = (0, location.1 + 1);
+        }
+
+        let start = *location;
+        location.0 += char_width;
+        start
+    }
+
+    /// Number of terminal columns used to render the grapheme cluster, or the mask if set
+    fn grapheme_width(&self, grapheme: &str) -> usize {
+        match self.mask {
+            Some(mask) => mask.width().unwrap_or(0),
+            None => grapheme.width(),
+        }
+    }
+
+    /// Render the part of a single line buffer that fits after the prompt, scrolled to keep
+    /// the cursor visible, with `‹` and `›` at the edges where the line continues
+    fn render_scrolled_buffer(&mut self, buffer: &StyledBuffer) -> Result<()> {
+        let available = self.terminal_size.0.saturating_sub(self.start_position.0) as usize;
+        let graphemes: Vec<(usize, usize, usize)> = buffer
+            .grapheme_boundaries()
+            .windows(2)
+            .map(|bounds| {
+                let grapheme = buffer.sub_string(bounds[0], bounds[1]).unwrap_or_default();
+                (bounds[0], bounds[1], self.grapheme_width(&grapheme))
+            })
+            .collect();
+
+        // Index of the cluster under the cursor, the cursor takes one column at the end
+        let cursor = graphemes
+            .iter()
+            .position(|g| g.0 >= buffer.position())
+            .unwrap_or(graphemes.len());
+        let cursor_width = graphemes.get(cursor).map_or(1, |g| g.2);
+
+        let total_width: usize = graphemes.iter().map(|g| g.2).sum();
+        let mut first = 0;
+        if total_width + 1 > available {
+            // Keep the previous window unless the cursor moved out of it
+            first = graphemes
+                .iter()
+                .position(|g| g.0 >= self.scroll_offset)
+                .unwrap_or(graphemes.len())
+                .min(cursor);
+
+            let has_right_indicator = cursor + 1 < graphemes.len();
+            while first < cursor {
+                let width: usize = graphemes[first..cursor].iter().map(|g| g.2).sum();
+                let used = usize::from(first > 0) + width + cursor_width;
+                if used + usize::from(has_right_indicator) <= available {
+                    break;
+                }
+                first += 1;
+            }
+        }
+        self.scroll_offset = graphemes.get(first).map_or(buffer.len(), |g| g.0);
+
+        // Take the clusters that fit, keeping a column for the right indicator if needed
+        let has_left_indicator = first > 0;
+        let limit = available.saturating_sub(usize::from(has_left_indicator));
+        let mut end = first;
+        let mut used = 0;
+        while end < graphemes.len() && used + graphemes[end].2 <= limit {
+            used += graphemes[end].2;
+            end += 1;
+        }
+        let has_right_indicator = end < graphemes.len();
+        while has_right_indicator && used + 1 > limit && end > first {
+            end -= 1;
+            used -= graphemes[end].2;
+        }
+
+        let mut indicator_style = Style::default();
+        indicator_style.set_foreground_color(Color::DarkGrey);
+
+        let styles = buffer.styles();
+        let mut window = StyledBuffer::default();
+        if has_left_indicator {
+            window.insert_styled_char('‹', indicator_style.clone());
+        }
+        for &(start, end, _) in &graphemes[first..end] {
+            if let Some(mask) = self.mask {
+                window.insert_styled_char(mask, styles[start].clone());
+                continue;
+            }
+            for (position, style) in styles.iter().enumerate().take(end).skip(start) {
+                if let Some(ch) = buffer.char_at(position) {
+                    window.insert_styled_char(ch, style.clone());
+                }
+            }
+        }
+        if has_right_indicator {
+            window.insert_styled_char('›', indicator_style);
+        }
+        view::base::render_styled_buffer(&mut self.stdout, &window)?;
+
+        let window_column = self.start_position.0 + u16::from(has_left_indicator);
+        self.scroll_window = Some(ScrollWindow {
+            start: self.scroll_offset,
+            end: graphemes.get(end).map_or(buffer.len(), |g| g.0),
+            column: window_column,
+        });
+
+        // Move the cursor to the current insertion position
+        let cursor_offset: usize = graphemes[first..cursor].iter().map(|g| g.2).sum();
+        let cursor_column = window_column + cursor_offset as u16;
+        self.stdout
+            .queue(cursor::MoveTo(cursor_column, self.start_position.1))?;
+        view::base::record_cursor_position(Some((cursor_column, self.start_position.1)));
+        Ok(())
+    }
+
+    /// Enable or Disable scrolling a single line buffer horizontally instead of wrapping it
+    /// when it is wider than the space after the prompt, disabled by default
+    pub fn set_horizontal_scroll(&mut self, enable: bool) {
+        self.horizontal_scroll = enable;
+        self.scroll_offset = 0;
+    }
+
+    /// Returns true if the buffer is rendered on rows below the row of the cursor,
+    /// where the views rendered under the cursor overlap it
+    pub fn has_rows_below_cursor(&self, buffer: &StyledBuffer) -> bool {
+        self.cursor_location(buffer, buffer.position()).1
+            < self.cursor_location(buffer, buffer.len()).1
+    }
+
+    /// Returns true if the cursor is displayed after the last visible character of the buffer,
+    /// where a hint can be rendered
+    pub fn is_cursor_at_end(&self, buffer: &StyledBuffer) -> bool {
+        self.layout_location(buffer, buffer.position())
+            == self.layout_location(buffer, buffer.len())
+    }
+
+    /// Translate a terminal column and row into the buffer position rendered there
+    ///
+    /// A click on the second column of a wide character lands on that character, a click
+    /// after the end of a line lands at the end of it. Returns None for a location outside
+    /// the rendered buffer, like the prompt, or if the line is rendered right to left
+    pub fn buffer_position_at(
+        &self,
+        buffer: &StyledBuffer,
+        column: u16,
+        row: u16,
+    ) -> Option<usize> {
+        if self.text_direction == TextDirection::RightToLeft || row < self.start_position.1 {
+            return None;
+        }
+
+        if let Some(window) = self.scroll_window {
+            if row != self.start_position.1 || column < window.column {
+                return None;
+            }
+
+            let mut start_column = window.column;
+            for bounds in buffer.grapheme_boundaries().windows(2) {
+                if bounds[0] < window.start || bounds[0] >= window.end {
+                    continue;
+                }
+                let grapheme = buffer.sub_string(bounds[0], bounds[1])?;
+                start_column += self.grapheme_width(&grapheme) as u16;
+                if column < start_column {
+                    return Some(bounds[0]);
+                }
+            }
+            return Some(window.end);
+        }
+
+        let row = row - self.start_position.1;
+        let mut location = (self.start_position.0, 0);
+        let mut row_start = None;
+        for bounds in buffer.grapheme_boundaries().windows(2) {
+            let i = bounds[0];
+            let grapheme = buffer.sub_string(bounds[0], bounds[1])?;
+            let (start_column, start_row) = self.advance_location(&grapheme, &mut location);
+            if start_row < row {
+                continue;
+            }
+
+            // The rest of a row that is wrapped before a wide character
+            if start_row > row {
+                return row_start.filter(|start| column >= *start).map(|_| i);
+            }
+
+            let row_start_column = *row_start.get_or_insert(start_column);
+            if column < row_start_column {
+                return None;
+            }
+
+            // The location is now after the cluster, on the same row unless it is a new line
+            if grapheme == "\n" || column < location.0 {
+                return Some(i);
+            }
+        }
+
+        if location.1 == row && column >= row_start.unwrap_or(location.0) {
+            return Some(buffer.len());
+        }
+        None
+    }
+
+    /// Render the buffer reversed and aligned to the right edge of the terminal,
+    /// then move the cursor to the visual insertion position
+    ///
+    /// This is a best effort layout, a line wider than the space after the prompt is not wrapped
+    fn render_right_to_left_buffer(&mut self, buffer: &StyledBuffer) -> Result<()> {
+        let styles = buffer.styles();
+        let mut reversed = StyledBuffer::default();
+        for i in (0..buffer.len()).rev() {
+            if let Some(ch) = buffer.char_at(i) {
+                reversed.insert_styled_char(ch, styles[i].clone());
+            }
+        }
+
+        let right_edge = u16::max(self.terminal_size.0, self.start_position.0 + 1);
+        let line_start = right_edge
+            .saturating_sub(buffer.len() as u16)
+            .max(self.start_position.0);
+        self.stdout.queue(cursor::MoveToColumn(line_start))?;
+        view::base::render_styled_buffer(&mut self.stdout, &reversed)?;
+
+        let cursor_column = (right_edge - 1)
+            .saturating_sub(buffer.position() as u16)
+            .max(self.start_position.0);
+        self.stdout.queue(cursor::MoveToColumn(cursor_column))?;
+        view::base::record_cursor_position(Some((cursor_column, self.start_position.1)));
+        Ok(())
+    }
+
     /// Receiving the insertion position on buffer and update the position on ui
     /// by calculating the right position using the prompt length
     pub fn update_cursor_position(&mut self, position: u16) -> Result<()> {
@@ -69,20 +596,67 @@ impl StyledEditorView {
         lines
     }
 
-    /// Render the prompt styled buffer
+    /// Render the prompt styled buffer at the start of the first row of the line
     pub fn render_prompt_buffer(&mut self, prompt: &StyledBuffer) -> Result<()> {
+        self.rendered_line = None;
+        self.stdout
+            .queue(cursor::MoveTo(0, self.start_position.1))?;
         view::base::render_styled_buffer(&mut self.stdout, prompt)?;
+        view::base::record_cursor_position(Some(self.start_position));
         self.flush()?;
         Ok(())
     }
 
-    /// Render hint at the end of buffer
+    /// Render hint at the end of buffer, the characters without a style of their own
+    /// are rendered with the hint style
     pub fn render_hint(&mut self, hint: &StyledBuffer) -> Result<()> {
-        view::base::render_styled_buffer(&mut self.stdout, hint)?;
+        // There is no room after the end of a right aligned line
+        if self.text_direction == TextDirection::RightToLeft {
+            return Ok(());
+        }
 
-        // Move the cursor to the current insertion position
-        let (column, _) = cursor::position()?;
-        self.stdout.queue(cursor::MoveToColumn(column))?;
+        // A scrolled line keeps the hint on the row instead of wrapping it
+        let (cursor_column, _) = view::base::cursor_position()?;
+        let mut hint_width = 0;
+        let max_hint_width = match self.scroll_window {
+            Some(_) => self.terminal_size.0.saturating_sub(cursor_column) as usize,
+            None => usize::MAX,
+        };
+
+        let mut styled_hint = StyledBuffer::default();
+        for (position, style) in hint.styles().iter().enumerate() {
+            let Some(ch) = hint.char_at(position) else {
+                continue;
+            };
+
+            hint_width += ch.width().unwrap_or(0);
+            if hint_width > max_hint_width {
+                break;
+            }
+
+            let is_unstyled = style.foreground_color().is_none()
+                && style.background_color().is_none()
+                && style.attributes().is_empty();
+            let style = if is_unstyled {
+                self.hint_style.clone()
+            } else {
+                style.clone()
+            };
+            styled_hint.insert_styled_char(ch, style);
+        }
+
+        // Keep the cursor at the insertion position, on its row if the hint wraps
+        self.stdout.queue(cursor::SavePosition)?;
+        view::base::render_styled_buffer(&mut self.stdout, &styled_hint)?;
+        self.stdout.queue(cursor::RestorePosition)?;
+
+        // The next render clears the rows the hint wrapped to
+        let columns = self.terminal_size.0 as usize;
+        if let Some(line) = self.rendered_line.as_mut().filter(|_| columns > 0) {
+            let hint_end = (cursor_column as usize + hint_width.min(max_hint_width)).max(1) - 1;
+            let hint_rows = (hint_end / columns) as u16;
+            line.rows = line.rows.max(self.cursor_row + hint_rows);
+        }
 
         // Flush the output stream
         self.stdout.flush()?;
@@ -96,14 +670,250 @@ impl StyledEditorView {
         Ok(())
     }
 
+    /// Set the target of the rendered line and the terminal control sequences
+    pub fn set_output(&mut self, output: Output) {
+        self.stdout = std::io::BufWriter::new(output);
+        self.rendered_line = None;
+    }
+
+    /// Set the direction used to lay out the line
+    pub fn set_text_direction(&mut self, direction: TextDirection) {
+        self.text_direction = direction;
+    }
+
+    /// Get the direction used to lay out the line
+    pub fn text_direction(&self) -> TextDirection {
+        self.text_direction
+    }
+
+    /// Set the prompt rendered flush to the right edge of the first line, or None to render
+    /// nothing there
+    pub fn set_right_prompt(&mut self, prompt: Option<String>) {
+        self.right_prompt = prompt.map(|prompt| StyledBuffer::from(prompt.as_str()));
+    }
+
+    /// Render the right prompt at the right edge of the first line, after the line
+    /// and the hint of the given width are rendered
+    ///
+    /// Nothing is rendered if the first line would reach the right prompt, rendering the line
+    /// again erases it, the cursor is kept at the insertion position
+    pub fn render_right_prompt(&mut self, buffer: &StyledBuffer, hint_width: usize) -> Result<()> {
+        let Some(right_prompt) = &self.right_prompt else {
+            return Ok(());
+        };
+
+        // A right aligned or scrolled line can use the whole row
+        if self.text_direction == TextDirection::RightToLeft || self.scroll_window.is_some() {
+            return Ok(());
+        }
+
+        let first_line_end = buffer
+            .literal()
+            .chars()
+            .position(|ch| ch == '\n')
+            .unwrap_or(buffer.len());
+        let (mut line_end_column, row) = self.layout_location(buffer, first_line_end);
+        if row > 0 {
+            return Ok(());
+        }
+
+        // The hint is rendered after the end of the buffer
+        if first_line_end == buffer.len() {
+            line_end_column = line_end_column.saturating_add(hint_width as u16);
+        }
+
+        let width = right_prompt.prompt_width() as u16;
+        let columns = self.terminal_size.0;
+        if line_end_column as usize + 1 + width as usize > columns as usize {
+            return Ok(());
+        }
+
+        self.stdout.queue(cursor::SavePosition)?;
+        self.stdout
+            .queue(cursor::MoveTo(columns - width, self.start_position.1))?;
+        view::base::render_styled_buffer(&mut self.stdout, right_prompt)?;
+        self.stdout.queue(cursor::RestorePosition)?;
+        self.flush()?;
+        Ok(())
+    }
+
+    /// Set the style of the hint characters that are not styled by the Hinter
+    pub fn set_hint_style(&mut self, style: Style) {
+        self.hint_style = style;
+    }
+
+    /// Set the character rendered instead of every grapheme cluster, or None to render the text
+    pub fn set_mask(&mut self, mask: Option<char>) {
+        self.rendered_line = None;
+        self.mask = mask;
+    }
+
+    /// Get the character rendered instead of every grapheme cluster if exists
+    pub fn mask(&self) -> Option<char> {
+        self.mask
+    }
+
+    /// Clear the terminal and move the cursor to the top left corner,
+    /// the prompt and the buffer should be rendered again
+    pub fn clear_screen(&mut self) -> Result<()> {
+        self.rendered_line = None;
+        self.stdout
+            .queue(terminal::Clear(terminal::ClearType::All))?;
+        self.stdout.queue(cursor::MoveTo(0, 0))?;
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    /// Update the cached terminal size, clear the line from the start of its first row and
+    /// move the cursor there, the prompt and the buffer should be rendered again
+    pub fn resize(&mut self, size: (u16, u16)) -> Result<()> {
+        self.rendered_line = None;
+        self.terminal_size = size;
+        self.start_position.1 = u16::min(self.start_position.1, size.1.saturating_sub(1));
+
+        self.stdout
+            .queue(cursor::MoveTo(0, self.start_position.1))?;
+        self.stdout
+            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    /// Render the whole line on the next render instead of the changed cells only,
+    /// after another view rendered over the line or cleared it
+    pub fn reset_rendered_line(&mut self) {
+        self.rendered_line = None;
+    }
+
     /// Set the current line start position, after prompt
     pub fn set_start_position(&mut self, position: (u16, u16)) {
+        if self.start_position != position {
+            self.rendered_line = None;
+        }
         self.start_position = position;
     }
 
+    /// Get the current line start position, after prompt
+    pub fn start_position(&self) -> (u16, u16) {
+        self.start_position
+    }
+
+    /// Set the prompt rendered at the start of every line after the first one
+    pub fn set_continuation_prompt(&mut self, prompt: String) {
+        self.rendered_line = None;
+        self.continuation_prompt_width = StyledBuffer::from(prompt.as_str()).prompt_width() as u16;
+        self.continuation_prompt = prompt;
+    }
+
     /// Flush the current output stream,
     pub fn flush(&mut self) -> Result<()> {
         self.stdout.flush()?;
         Ok(())
     }
 }
+
+/// Render the buffer with its styles to a String of text and ANSI escape sequences,
+/// the same output the views write to the terminal, for example to log a highlighted line
+/// or compare the output of a Highlighter with the expected one
+pub fn render_to_ansi(buffer: &StyledBuffer) -> String {
+    let mut output = vec![];
+
+    // Writing to memory does not fail
+    let _ = view::base::render_styled_buffer(&mut output, buffer);
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+/// Create a copy of the buffer with the mask instead of every grapheme cluster except new lines,
+/// every mask character keeps the style of the cluster and the cursor stays on the same cluster
+fn masked_buffer(buffer: &StyledBuffer, mask: char) -> StyledBuffer {
+    let styles = buffer.styles();
+    let mut masked = StyledBuffer::default();
+    let mut position = 0;
+    for bounds in buffer.grapheme_boundaries().windows(2) {
+        if bounds[0] < buffer.position() {
+            position += 1;
+        }
+
+        let ch = match buffer.char_at(bounds[0]) {
+            Some('\n') => '\n',
+            _ => mask,
+        };
+        masked.insert_styled_char(ch, styles[bounds[0]].clone());
+    }
+    masked.set_position(position);
+    masked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::testing::SharedOutput;
+
+    fn view(columns: u16) -> StyledEditorView {
+        let mut view = StyledEditorView::default();
+        view.set_output(Output::new(Box::new(std::io::sink())));
+        view.terminal_size = (columns, 10);
+        view.set_start_position((2, 0));
+        view
+    }
+
+    #[test]
+    fn cursor_column_after_japanese_text() {
+        let view = view(40);
+        let mut buffer = StyledBuffer::from("日本語のテキスト");
+        assert_eq!(view.cursor_location(&buffer, buffer.len()), (18, 0));
+
+        buffer.set_position(3);
+        assert_eq!(view.cursor_location(&buffer, buffer.position()), (8, 0));
+        assert!(!view.is_cursor_at_end(&buffer));
+
+        buffer.set_position(buffer.len());
+        assert!(view.is_cursor_at_end(&buffer));
+    }
+
+    #[test]
+    fn wrap_wide_character_that_does_not_fit() {
+        // The prompt takes two columns, the fourth wide character does not fit in nine
+        let view = view(9);
+        let buffer = StyledBuffer::from("日本語の");
+        assert_eq!(view.cursor_location(&buffer, 3), (8, 0));
+        assert_eq!(view.cursor_location(&buffer, 4), (2, 1));
+    }
+
+    #[test]
+    fn render_changed_line_without_clearing_below() {
+        let bytes = Rc::new(RefCell::new(vec![]));
+        let mut view = view(10);
+        view.set_output(Output::new(Box::new(SharedOutput(bytes.clone()))));
+        view.render_prompt_buffer(&StyledBuffer::from("> "))
+            .unwrap();
+        view.render_line_buffer(&StyledBuffer::from("hello world!"))
+            .unwrap();
+
+        // A view rendered under the line that restores the cursor
+        bytes
+            .borrow_mut()
+            .extend_from_slice(b"\x1b7\x1b[4;1Hmenu\x1b8");
+        let rendered = bytes.borrow().len();
+
+        view.render_line_buffer(&StyledBuffer::from("hello"))
+            .unwrap();
+        let output = String::from_utf8(bytes.borrow().clone()).unwrap();
+        assert!(!output[rendered..].contains("\x1b[J"));
+        assert_eq!(
+            crate::testing::replay((10, 10), &output),
+            "> hello\n\n\nmenu"
+        );
+
+        view.render_line_buffer(&StyledBuffer::from("hello you"))
+            .unwrap();
+        let output = String::from_utf8(bytes.borrow().clone()).unwrap();
+        assert_eq!(
+            crate::testing::replay((10, 10), &output),
+            "> hello yo\nu\n\nmenu"
+        );
+    }
+}